@@ -16,25 +16,22 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use lib_xch::public::{
     handler::Handler,
+    solvers::{FractionFree, GaussJordan, Modular, NullSpaceSolver},
     traits::{CheckedCalc, CheckedType},
 };
 
-const EQU_1: &'static str = "H2+Ca(CN)2+NaAlF4+FeSO4+MgSiO3+KI+H3PO4+PbCrO4+BrCl+CF2Cl2+SO2=PbBr2+CrCl3+MgCO3+KAl(OH)4+Fe(SCN)3+PI3+Na2SiO3+CaF2+H2O";
-const EQU_2: &'static str = "NH4ClO4+HNO3+HCl=HClO4+N2O+Cl2+H2O";
+const EQU_1: &str = "H2+Ca(CN)2+NaAlF4+FeSO4+MgSiO3+KI+H3PO4+PbCrO4+BrCl+CF2Cl2+SO2=PbBr2+CrCl3+MgCO3+KAl(OH)4+Fe(SCN)3+PI3+Na2SiO3+CaF2+H2O";
+const EQU_2: &str = "NH4ClO4+HNO3+HCl=HClO4+N2O+Cl2+H2O";
 
-fn handle<T: CheckedType + CheckedCalc>(equ: &str)
-where
-    std::num::ParseIntError: std::convert::From<<T as num::Num>::FromStrRadixErr>
-        + std::convert::From<<T as std::str::FromStr>::Err>,
-{
+fn handle<T: CheckedType + CheckedCalc>(equ: &str) {
     Handler::<T>::new(equ).handle().unwrap();
 }
 
-fn parse<T: CheckedType + CheckedCalc>(equ: &str)
-where
-    std::num::ParseIntError: std::convert::From<<T as num::Num>::FromStrRadixErr>
-        + std::convert::From<<T as std::str::FromStr>::Err>,
-{
+fn handle_with<T: CheckedType + CheckedCalc, S: NullSpaceSolver<T>>(equ: &str, solver: S) {
+    Handler::<T>::new(equ).solver(solver).handle().unwrap();
+}
+
+fn parse<T: CheckedType + CheckedCalc>(equ: &str) {
     Handler::<T>::new(equ).parse().unwrap();
 }
 
@@ -56,5 +53,39 @@ fn bench_parse(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_parse, bench_handle);
+// The parser alone, on the longest equation
+fn bench_parse_equ_1(c: &mut Criterion) {
+    c.bench_function("parse_equ_1", |b| b.iter(|| parse::<isize>(EQU_1)));
+}
+
+fn bench_solvers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solvers");
+    group.bench_function("gauss_jordan", |b| {
+        b.iter(|| {
+            handle_with::<isize, _>(EQU_1, GaussJordan);
+            handle_with::<isize, _>(EQU_2, GaussJordan);
+        })
+    });
+    group.bench_function("fraction_free", |b| {
+        b.iter(|| {
+            handle_with::<isize, _>(EQU_1, FractionFree);
+            handle_with::<isize, _>(EQU_2, FractionFree);
+        })
+    });
+    group.bench_function("modular", |b| {
+        b.iter(|| {
+            handle_with::<isize, _>(EQU_1, Modular);
+            handle_with::<isize, _>(EQU_2, Modular);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_parse,
+    bench_parse_equ_1,
+    bench_handle,
+    bench_solvers
+);
 criterion_main!(benches);
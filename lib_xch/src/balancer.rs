@@ -16,4 +16,4 @@
 // Overall: This is the source code of the AlphaForce Balancer.
 
 pub mod handler;
-mod maths;
+pub mod maths;
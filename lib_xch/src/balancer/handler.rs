@@ -15,31 +15,169 @@
 
 // Overall: This is the source code of the AlphaForce Balancer.
 
-use nalgebra::base::{Dynamic, MatrixMN};
-use num::rational::Ratio;
+use num::Zero;
 use std::vec::Vec;
 // inside use(s)
-use super::maths::g_elim::GaussianElimination;
-use crate::public::{failures::ErrorCases, traits::CheckedType, types::DataSetIn};
+use super::maths::{cone::ExtremeRays, positive::MinimalPositive, NullSpaceSolver};
+use crate::public::{
+    calc::{safe_calc, Operator},
+    failures::{
+        ErrorCases,
+        ErrorCases::{NoPositiveSolution, SearchLimit, ZeroSolution},
+    },
+    traits::{CheckedCalc, CheckedType},
+    types::{DataSetIn, DiagnoseOutput},
+};
 
-pub fn balancer<T: CheckedType>(ds: DataSetIn<T>) -> Result<Vec<Vec<T>>, ErrorCases> {
+// The maximum number of the elementary reactions which the warnings and `minimal_positive` look through
+const RAY_LIMIT: usize = 1 << 10;
+
+pub fn balancer<T>(
+    ds: DataSetIn<T>,
+    solver: &dyn NullSpaceSolver<T>,
+) -> Result<Vec<Vec<T>>, ErrorCases> {
     let (cd, data) = ds;
-    let v = data
-        .iter()
-        .flat_map(|x| x)
-        .map(|x| Ratio::<T>::from_integer(*x))
+    solver.solve(data, cd.sum)
+}
+
+pub fn minimal_positive<T: CheckedType + CheckedCalc>(
+    basis: &[Vec<T>],
+) -> Result<Vec<T>, ErrorCases> {
+    let search = MinimalPositive::<T>::new(basis)?;
+    let rays = match ExtremeRays::<T>::new(basis)?.limit(RAY_LIMIT).solve() {
+        Ok(rays) => rays,
+        // Too many to bound the search with, which still works without the bound.
+        Err(SearchLimit) => return search.solve(),
+        Err(e) => return Err(e),
+    };
+    // The sum of all the elementary reactions is positive if there is any positive solution.
+    let mut sum = vec![T::zero(); basis.first().map_or(0, Vec::len)];
+    for ray in rays {
+        for (s, x) in sum.iter_mut().zip(ray.iter()) {
+            *s = safe_calc(s, x, &Operator::Add)?;
+        }
+    }
+    if sum.iter().any(Zero::is_zero) {
+        return Err(NoPositiveSolution);
+    }
+    search.bound(sum)?.solve()
+}
+
+// The extreme rays of the cone of non-negative solutions.
+pub fn elementary_reactions<T: CheckedType + CheckedCalc>(
+    basis: &[Vec<T>],
+) -> Result<Vec<Vec<T>>, ErrorCases> {
+    ExtremeRays::<T>::new(basis)?.solve()
+}
+
+// Returns the columns which are zero in every solution,
+// and the columns which are never positive in a non-negative solution unless they are negated.
+// The warnings are best-effort: the second ones are left out if they can't be computed, e.g. on overflow.
+pub fn warnings<T: CheckedType + CheckedCalc>(basis: &[Vec<T>]) -> (Vec<usize>, Vec<usize>) {
+    let m = basis.first().map_or(0, Vec::len);
+    let zeros = (0..m)
+        .filter(|j| basis.iter().all(|b| b[*j].is_zero()))
         .collect::<Vec<_>>();
-    let equation_matrix =
-        MatrixMN::<Ratio<T>, Dynamic, Dynamic>::from_row_slice(data.len(), cd.sum, &v[..]);
-    let ans = GaussianElimination::<T>::new(equation_matrix).solve()?;
-    let result = ans
-        .into_iter()
-        .map(|v| {
-            let lcm: T = v.iter().fold(T::one(), |lcm, ratio| lcm.lcm(ratio.denom()));
-            v.into_iter()
-                .map(|ratio| lcm / *ratio.denom() * *ratio.numer())
-                .collect::<Vec<_>>()
+    let wrong_side = wrong_side(basis, &zeros).unwrap_or_default();
+    (zeros, wrong_side)
+}
+
+fn wrong_side<T: CheckedType + CheckedCalc>(
+    basis: &[Vec<T>],
+    zeros: &[usize],
+) -> Result<Vec<usize>, ErrorCases> {
+    let m = basis.first().map_or(0, Vec::len);
+    let rays = ExtremeRays::<T>::new(basis)?.limit(RAY_LIMIT).solve()?;
+    let mut wrong_side = Vec::<usize>::new();
+    for j in (0..m).filter(|j| !zeros.contains(j) && rays.iter().all(|r| !r[*j].is_positive())) {
+        // Move the column to the other side.
+        let mut flipped = basis.to_vec();
+        for b in flipped.iter_mut() {
+            b[j] = safe_calc(&b[j], &T::one(), &Operator::Neg)?;
+        }
+        if ExtremeRays::<T>::new(&flipped)?
+            .limit(RAY_LIMIT)
+            .solve()?
+            .iter()
+            .any(|r| r[j].is_positive())
+        {
+            wrong_side.push(j);
+        }
+    }
+    Ok(wrong_side)
+}
+
+// Returns the totals of every row on the left side and on the right side for the coefficients.
+// The columns of the right side are negated in the matrix, so they are negated back.
+pub fn totals<T: CheckedType + CheckedCalc>(
+    ds: DataSetIn<T>,
+    x: &[T],
+) -> Result<Vec<(T, T)>, ErrorCases> {
+    let (cd, data) = ds;
+    data.iter()
+        .map(|row| {
+            let (mut left, mut right) = (T::zero(), T::zero());
+            for (j, (a, c)) in row.iter().zip(x.iter()).enumerate() {
+                let p = safe_calc(a, c, &Operator::Mul)?;
+                if j < cd.left {
+                    left = safe_calc(&left, &p, &Operator::Add)?;
+                } else {
+                    right = safe_calc(&right, &p, &Operator::Sub)?;
+                }
+            }
+            Ok((left, right))
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
+// Returns the rows which only appear on one side, the columns which are zero in every solution,
+// and a minimal subset of rows which still forces all of these columns to zero.
+pub fn diagnose<T: CheckedType>(
+    ds: DataSetIn<T>,
+    solver: &dyn NullSpaceSolver<T>,
+) -> Result<DiagnoseOutput, ErrorCases> {
+    let (cd, data) = ds;
+    let one_sided = data
+        .iter()
+        .enumerate()
+        .filter(|(_, row)| {
+            let (left, right) = row.split_at(cd.left);
+            left.iter().all(Zero::is_zero) != right.iter().all(Zero::is_zero)
         })
+        .map(|(i, _)| i)
         .collect::<Vec<_>>();
-    Ok(result)
+    let zeros = zero_columns(data, cd.sum, solver)?;
+    let mut rows = Vec::<usize>::new();
+    if !zeros.is_empty() {
+        // Greedy deletion: drop every row which isn't needed to force the columns to zero.
+        rows = (0..data.len()).collect::<Vec<_>>();
+        let mut i = 0;
+        while i < rows.len() {
+            let mut rest = rows.clone();
+            rest.remove(i);
+            let matrix = rest.iter().map(|r| data[*r].clone()).collect::<Vec<_>>();
+            if zero_columns(&matrix, cd.sum, solver)? == zeros {
+                rows = rest;
+            } else {
+                i += 1;
+            }
+        }
+    }
+    Ok((one_sided, zeros, rows))
+}
+
+// The columns which are zero in every solution.
+fn zero_columns<T: CheckedType>(
+    matrix: &[Vec<T>],
+    m: usize,
+    solver: &dyn NullSpaceSolver<T>,
+) -> Result<Vec<usize>, ErrorCases> {
+    let basis = match solver.solve(matrix, m) {
+        Ok(basis) => basis,
+        Err(ZeroSolution) => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    Ok((0..m)
+        .filter(|j| basis.iter().all(|b| b[*j].is_zero()))
+        .collect::<Vec<_>>())
 }
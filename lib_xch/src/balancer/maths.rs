@@ -15,4 +15,212 @@
 
 // Overall: This is the source code of the Hyper Mathlib.
 
-pub mod g_elim; // In case there will be more methods in the future
+pub mod bareiss;
+pub mod cone;
+pub mod g_elim;
+pub mod modular;
+pub mod positive;
+
+use num::{bigint::BigInt, rational::Ratio, Integer};
+// inside use(s)
+use crate::public::{
+    calc::{safe_calc, Operator},
+    cell::Cell,
+    failures::{
+        ErrorCases,
+        ErrorCases::{Overflow, ZeroSolution},
+    },
+    traits::{CheckedCalc, CheckedType},
+};
+use bareiss::Bareiss;
+use g_elim::GaussianElimination;
+use modular::MultiModular;
+
+/// A method which computes the Basic Solutions, which are a basis of the null space of the matrix.
+///
+/// Implement it to plug your own solver into `Handler::solver`.
+pub trait NullSpaceSolver<T> {
+    /// Compute the Basic Solutions of `matrix`, which has `m` columns.
+    ///
+    /// Every Basic Solution should be an integer vector `x` whose length is `m` and which satisfies `matrix * x = 0`.
+    /// It should return `Err(ErrorCases::ZeroSolution)` if there is no Basic Solution at all.
+    ///
+    /// Any basis of the null space will do. The searches which need a column touched by only one Basic Solution,
+    /// like `Handler::minimal_positive`, bring the basis into the reduced row echelon form first.
+    fn solve(&self, matrix: &[Vec<T>], m: usize) -> Result<Vec<Vec<T>>, ErrorCases>;
+}
+
+impl<T, S: NullSpaceSolver<T> + ?Sized> NullSpaceSolver<T> for Box<S> {
+    fn solve(&self, matrix: &[Vec<T>], m: usize) -> Result<Vec<Vec<T>>, ErrorCases> {
+        (**self).solve(matrix, m)
+    }
+}
+
+/// Gaussian-Jordan Elimination on rational numbers. It is the default solver.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GaussJordan;
+
+/// Fraction-free Gaussian-Jordan Elimination (Bareiss Algorithm), which keeps every entry an integer.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FractionFree;
+
+/// The Multi-modular Method, which solves the matrix modulo several primes,
+/// then reconstructs the Basic Solutions by the Chinese Remainder Theorem and the rational reconstruction.
+///
+/// It works on arbitrary-precision integers inside, so it only overflows if the Basic Solutions don't fit in `T`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Modular;
+
+impl<T: CheckedType + CheckedCalc> NullSpaceSolver<T> for GaussJordan {
+    fn solve(&self, matrix: &[Vec<T>], m: usize) -> Result<Vec<Vec<T>>, ErrorCases> {
+        in_cells(matrix, |matrix| {
+            let equation_matrix = matrix
+                .into_iter()
+                .map(|row| row.into_iter().map(Ratio::from_integer).collect::<Vec<_>>())
+                .collect::<Vec<_>>();
+            let ans = GaussianElimination::new(equation_matrix, m).solve()?;
+            Ok(ans
+                .into_iter()
+                .map(|v| {
+                    let lcm = v
+                        .iter()
+                        .fold(Cell::new(T::one()), |lcm, ratio| lcm.lcm(ratio.denom()));
+                    v.into_iter()
+                        .map(|ratio| lcm.clone() / ratio.denom().clone() * ratio.numer().clone())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>())
+        })
+    }
+}
+
+impl<T: CheckedType + CheckedCalc> NullSpaceSolver<T> for FractionFree {
+    fn solve(&self, matrix: &[Vec<T>], m: usize) -> Result<Vec<Vec<T>>, ErrorCases> {
+        in_cells(matrix, |matrix| Bareiss::new(matrix, m).solve())
+    }
+}
+
+impl<T: CheckedType + CheckedCalc> NullSpaceSolver<T> for Modular {
+    fn solve(&self, matrix: &[Vec<T>], m: usize) -> Result<Vec<Vec<T>>, ErrorCases> {
+        let matrix = matrix
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| x.to_string().parse::<BigInt>().map_err(|_| Overflow))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        MultiModular::new(matrix, m)
+            .solve()?
+            .into_iter()
+            .map(|v| {
+                v.into_iter()
+                    .map(|x| x.to_string().parse::<T>().map_err(|_| Overflow))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+}
+
+// Run the solver on `Cell`s, so that any overflow is reported instead of giving a wrong answer.
+fn in_cells<T: CheckedType + CheckedCalc, F>(
+    matrix: &[Vec<T>],
+    f: F,
+) -> Result<Vec<Vec<T>>, ErrorCases>
+where
+    F: FnOnce(Vec<Vec<Cell<T>>>) -> Result<Vec<Vec<Cell<T>>>, ErrorCases>,
+{
+    let cells = matrix
+        .iter()
+        .map(|row| row.iter().cloned().map(Cell::new).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    f(cells)?
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|x| {
+                    if x.get_tag() {
+                        Err(Overflow)
+                    } else {
+                        Ok(x.get_data().clone())
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
+// For each basic solution, find a column which only this basic solution touches.
+// The Basic Solutions given by the built-in solvers always have these columns (the free variables).
+pub fn free_columns<T: CheckedType>(basis: &[Vec<T>]) -> Option<Vec<usize>> {
+    let n = basis.first().map_or(0, Vec::len);
+    (0..basis.len())
+        .map(|i| {
+            (0..n).find(|j| {
+                !basis[i][*j].is_zero()
+                    && (0..basis.len()).all(|l| l == i || basis[l][*j].is_zero())
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+}
+
+// Returns a basis of the same space which has the free columns, and the free columns.
+// A basis from a custom solver is brought into the reduced row echelon form, without fractions.
+// Linearly dependent vectors are dropped.
+pub fn reduced_basis<T: CheckedType + CheckedCalc>(
+    basis: &[Vec<T>],
+) -> Result<(Vec<Vec<T>>, Vec<usize>), ErrorCases> {
+    if let Some(columns) = free_columns(basis) {
+        return Ok((basis.to_vec(), columns));
+    }
+    let mut rows = basis.to_vec();
+    let n = rows.first().map_or(0, Vec::len);
+    let mut r = 0;
+    for j in 0..n {
+        if r == rows.len() {
+            break;
+        }
+        let p = match (r..rows.len()).find(|i| !rows[*i][j].is_zero()) {
+            Some(p) => p,
+            None => continue,
+        };
+        rows.swap(r, p);
+        for u in (0..rows.len()).filter(|u| *u != r) {
+            if rows[u][j].is_zero() {
+                continue;
+            }
+            let (a, b) = (rows[r][j].clone(), rows[u][j].clone());
+            // row_u = a * row_u - b * row_r
+            let row = rows[u]
+                .iter()
+                .zip(rows[r].iter())
+                .map(|(x, y)| {
+                    safe_calc(
+                        &safe_calc(x, &a, &Operator::Mul)?,
+                        &safe_calc(y, &b, &Operator::Mul)?,
+                        &Operator::Sub,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let gcd = row.iter().fold(T::zero(), |gcd, x| gcd.gcd(x));
+            rows[u] = if gcd.is_zero() {
+                row
+            } else {
+                row.into_iter().map(|x| x / gcd.clone()).collect::<Vec<_>>()
+            };
+        }
+        r += 1;
+    }
+    // The rest are zero.
+    rows.truncate(r);
+    let columns = free_columns(&rows).ok_or(ZeroSolution)?;
+    Ok((rows, columns))
+}
+
+pub fn safe_lcm<T: CheckedType + CheckedCalc>(a: &T, b: &T) -> Result<T, ErrorCases> {
+    if a.is_zero() || b.is_zero() {
+        return Ok(T::zero());
+    }
+    let lcm = safe_calc(&safe_calc(a, &a.gcd(b), &Operator::Div)?, b, &Operator::Mul)?;
+    Ok(lcm.abs())
+}
@@ -0,0 +1,128 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// Overall: This is the source code of the Hyper Mathlib.
+
+// inside use(s)
+use crate::public::{
+    failures::{ErrorCases, ErrorCases::ZeroSolution},
+    traits::CheckedType,
+};
+
+// The fraction-free Gauss-Jordan Elimination (Bareiss Algorithm).
+// Every entry stays an integer, since the division by the previous pivot is always exact.
+// After the elimination, all the pivots equal to the same determinant `d`.
+pub struct Bareiss<T: CheckedType> {
+    matrix_a: Vec<Vec<T>>, // A n*m matrix.
+    n: usize,
+    m: usize,
+}
+
+impl<T: CheckedType> Bareiss<T> {
+    pub fn new(matrix_a: Vec<Vec<T>>, m: usize) -> Self {
+        // Create a Bareiss Solution.
+        let n = matrix_a.len();
+        Self { matrix_a, n, m }
+    }
+
+    pub fn solve(mut self) -> Result<Vec<Vec<T>>, ErrorCases> {
+        let mut prev = T::one();
+        let mut pivots = Vec::<usize>::new(); // the pivot column of each row
+        for j in 0..self.m {
+            let r = pivots.len();
+            if r == self.n {
+                break;
+            }
+            let max_row = match self.get_max_abs_row(r, j) {
+                Some(s) => s,
+                None => continue,
+            };
+            self.matrix_a.swap(r, max_row);
+            let pivot = self.matrix_a[r][j].clone();
+            for u in (0..self.n).filter(|u| *u != r) {
+                let factor = self.matrix_a[u][j].clone();
+                for k in 0..self.m {
+                    // A_{u}{k}=(p*A_{u}{k}-A_{u}{j}*A_{r}{k})/prev
+                    self.matrix_a[u][k] = (pivot.clone() * self.matrix_a[u][k].clone()
+                        - factor.clone() * self.matrix_a[r][k].clone())
+                        / prev.clone();
+                }
+            }
+            pivots.push(j);
+            prev = pivot;
+        }
+        // x_{free}=d, x_{pivot}=-A_{i}{free}
+        let ans = (0..self.m)
+            .filter(|j| !pivots.contains(j))
+            .map(|f| {
+                let mut v = vec![T::zero(); self.m];
+                v[f] = prev.clone();
+                for (i, p) in pivots.iter().enumerate() {
+                    v[*p] = -self.matrix_a[i][f].clone();
+                }
+                normalize(v, f)
+            })
+            .collect::<Vec<_>>();
+        if ans.is_empty() {
+            Err(ZeroSolution)
+        } else {
+            Ok(ans)
+        }
+    }
+
+    fn get_max_abs_row(&self, row: usize, column: usize) -> Option<usize> {
+        let mut maxi = row;
+        for k in row + 1..self.n {
+            if self.matrix_a[k][column].abs() > self.matrix_a[maxi][column].abs() {
+                maxi = k;
+            }
+        }
+        if self.matrix_a[maxi][column].is_zero() {
+            None
+        } else {
+            Some(maxi)
+        }
+    }
+}
+
+// Divide the solution by the GCD of its entries, and make the free variable positive.
+fn normalize<T: CheckedType>(v: Vec<T>, free: usize) -> Vec<T> {
+    let gcd = v.iter().fold(T::zero(), |gcd, x| gcd.gcd(x));
+    let sign = v[free].signum();
+    v.into_iter()
+        .map(|x| x / gcd.clone() * sign.clone())
+        .collect::<Vec<_>>()
+}
+
+// unit tests
+#[cfg(test)]
+mod tests {
+    use super::Bareiss;
+
+    #[test]
+    fn solve() {
+        // KClO3+HCl=KCl+ClO2+Cl2+H2O
+        let matrix = vec![
+            vec![1, 0, -1, 0, 0, 0],
+            vec![1, 1, -1, -1, -2, 0],
+            vec![3, 0, 0, -2, 0, -1],
+            vec![0, 1, 0, 0, 0, -2],
+        ];
+        assert_eq!(
+            Bareiss::<i32>::new(matrix, 6).solve().unwrap(),
+            vec![vec![-4, 0, -4, -6, 3, 0], vec![5, 6, 5, 6, 0, 3]]
+        );
+    }
+}
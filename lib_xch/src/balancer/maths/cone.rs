@@ -0,0 +1,176 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// Overall: This is the source code of the Hyper Mathlib.
+
+// inside use(s)
+use super::reduced_basis;
+use crate::public::{
+    calc::{safe_calc, Operator},
+    failures::{
+        ErrorCases,
+        ErrorCases::{SearchLimit, ZeroSolution},
+    },
+    traits::{CheckedCalc, CheckedType},
+};
+
+// The Double Description Method.
+// The cone is `{x | x = lambda_1 * b_1 + ... + lambda_k * b_k, x >= 0}`.
+// Constraining the free columns only gives the orthant spanned by the Basic Solutions,
+// then the other columns are constrained one by one.
+pub struct ExtremeRays<T: CheckedType + CheckedCalc> {
+    rays: Vec<Vec<T>>,
+    processed: Vec<usize>, // The columns which have been constrained
+    rest: Vec<usize>,
+    limit: Option<usize>, // The maximum number of rays
+}
+
+impl<T: CheckedType + CheckedCalc> ExtremeRays<T> {
+    pub fn new(basis: &[Vec<T>]) -> Result<Self, ErrorCases> {
+        let (basis, processed) = reduced_basis(basis)?;
+        if basis.is_empty() {
+            return Err(ZeroSolution);
+        }
+        let rest = (0..basis[0].len())
+            .filter(|j| !processed.contains(j))
+            .collect::<Vec<_>>();
+        let rays = basis
+            .iter()
+            .zip(processed.iter())
+            .map(|(b, j)| {
+                if b[*j].is_negative() {
+                    b.iter()
+                        .map(|x| safe_calc(x, &T::one(), &Operator::Neg))
+                        .collect::<Result<Vec<_>, _>>()
+                } else {
+                    Ok(b.to_vec())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .map(normalize)
+            .collect::<Vec<_>>();
+        Ok(Self {
+            rays,
+            processed,
+            rest,
+            limit: None,
+        })
+    }
+
+    // The number of the rays may grow exponentially, so give up with `SearchLimit` once there are more than `limit`.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn solve(mut self) -> Result<Vec<Vec<T>>, ErrorCases> {
+        for j in self.rest.clone() {
+            let mut rays = Vec::<Vec<T>>::new();
+            let (mut pos, mut neg) = (Vec::<usize>::new(), Vec::<usize>::new());
+            for (i, r) in self.rays.iter().enumerate() {
+                if r[j].is_positive() {
+                    pos.push(i);
+                } else if r[j].is_negative() {
+                    neg.push(i);
+                }
+                if !r[j].is_negative() {
+                    rays.push(r.to_vec());
+                }
+            }
+            for p in pos.iter() {
+                for q in neg.iter() {
+                    if self.adjacent(*p, *q) {
+                        rays.push(self.combine(*p, *q, j)?);
+                    }
+                }
+            }
+            if self.limit.is_some_and(|limit| rays.len() > limit) {
+                return Err(SearchLimit);
+            }
+            self.rays = rays;
+            self.processed.push(j);
+        }
+        self.rays.sort();
+        Ok(self.rays)
+    }
+
+    // Combinatorial test: two rays are adjacent if and only if
+    // no other ray is zero on all the constrained columns where both of them are zero.
+    fn adjacent(&self, p: usize, q: usize) -> bool {
+        let common = self
+            .processed
+            .iter()
+            .filter(|c| self.rays[p][**c].is_zero() && self.rays[q][**c].is_zero())
+            .collect::<Vec<_>>();
+        !self
+            .rays
+            .iter()
+            .enumerate()
+            .any(|(i, r)| i != p && i != q && common.iter().all(|c| r[**c].is_zero()))
+    }
+
+    // Combine a positive ray and a negative ray so that the result is zero on the column `j`.
+    fn combine(&self, p: usize, q: usize, j: usize) -> Result<Vec<T>, ErrorCases> {
+        let (a, b) = (
+            self.rays[p][j].clone(),
+            safe_calc(&self.rays[q][j], &T::one(), &Operator::Neg)?,
+        );
+        let ray = self.rays[p]
+            .iter()
+            .zip(self.rays[q].iter())
+            .map(|(x, y)| {
+                safe_calc(
+                    &safe_calc(x, &b, &Operator::Mul)?,
+                    &safe_calc(y, &a, &Operator::Mul)?,
+                    &Operator::Add,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(normalize(ray))
+    }
+}
+
+// Divide the ray by the GCD of its entries.
+fn normalize<T: CheckedType>(ray: Vec<T>) -> Vec<T> {
+    let gcd = ray.iter().fold(T::zero(), |gcd, x| gcd.gcd(x));
+    if gcd.is_zero() || gcd.is_one() {
+        ray
+    } else {
+        ray.into_iter().map(|x| x / gcd.clone()).collect::<Vec<_>>()
+    }
+}
+
+// unit tests
+#[cfg(test)]
+mod tests {
+    use super::ExtremeRays;
+
+    #[test]
+    fn rays() {
+        // Cu+HNO3=Cu(NO3)2+NO+NO2+H2O
+        let basis = vec![vec![-1, 0, -1, -2, 4, 0], vec![3, 8, 3, 2, 0, 4]];
+        assert_eq!(
+            ExtremeRays::<i32>::new(&basis).unwrap().solve().unwrap(),
+            vec![vec![1, 4, 1, 0, 2, 2], vec![3, 8, 3, 2, 0, 4]]
+        );
+        // A+A=B
+        let basis = vec![vec![-1, 1, 0]];
+        assert_eq!(
+            ExtremeRays::<i32>::new(&basis).unwrap().solve().unwrap(),
+            Vec::<Vec<i32>>::new()
+        );
+    }
+}
@@ -15,7 +15,6 @@
 
 // Overall: This is the source code of the Hyper Mathlib.
 
-use nalgebra::base::{Dynamic, MatrixMN};
 use num::{rational::Ratio, One, Signed, Zero};
 // inside use(s)
 use crate::public::{
@@ -24,15 +23,15 @@ use crate::public::{
 };
 
 pub struct GaussianElimination<T: CheckedType> {
-    matrix_a: MatrixMN<Ratio<T>, Dynamic, Dynamic>, // A n*m matrix.
+    matrix_a: Vec<Vec<Ratio<T>>>, // A n*m matrix.
     n: usize,
     m: usize,
 }
 
 impl<T: CheckedType> GaussianElimination<T> {
-    pub fn new(matrix_a: MatrixMN<Ratio<T>, Dynamic, Dynamic>) -> Self {
+    pub fn new(matrix_a: Vec<Vec<Ratio<T>>>, m: usize) -> Self {
         // Create a GaussianElimination Solution.
-        let (n, m) = matrix_a.shape();
+        let n = matrix_a.len();
         Self { matrix_a, n, m }
     }
 
@@ -52,17 +51,14 @@ impl<T: CheckedType> GaussianElimination<T> {
                 None => continue, // if most left row has no pivot, just continue.
             };
             let max_row = self.get_max_abs_row(i, j);
-            if self.matrix_a[(max_row, j)] != Ratio::<T>::zero() {
-                self.matrix_a.swap_rows(i, max_row); // swap row i and maxi in matrix_a
-                {
-                    let tmp = &(self.matrix_a.row(i) / self.matrix_a[(i, j)]);
-                    self.matrix_a.row_mut(i).copy_from(tmp);
-                }
+            if self.matrix_a[max_row][j] != Ratio::<T>::zero() {
+                self.matrix_a.swap(i, max_row); // swap row i and maxi in matrix_a
+                let pivot = self.matrix_a[i][j].clone();
+                self.matrix_a[i]
+                    .iter_mut()
+                    .for_each(|item| *item = item.clone() / pivot.clone());
                 for u in i + 1..self.n {
-                    let v = self.matrix_a.row(i) * self.matrix_a[(u, j)];
-                    for (k, item) in v.iter().enumerate().take(self.m) {
-                        self.matrix_a[(u, k)] -= *item; // A_{u}=A_{u}-A_{u}{j}*A_{i}
-                    }
+                    self.eliminate(i, u, j); // A_{u}=A_{u}-A_{u}{j}*A_{i}
                 }
             }
         } // REF
@@ -73,26 +69,21 @@ impl<T: CheckedType> GaussianElimination<T> {
             };
             for u in (0..i).rev() {
                 // j above i
-                let v = self.matrix_a.row(i) * self.matrix_a[(u, j)];
-                for (k, item) in v.iter().enumerate().take(self.m) {
-                    self.matrix_a[(u, k)] -= *item; // A_{u}=A_{u}-A_{u}{j}*A_{i}
-                }
+                self.eliminate(i, u, j); // A_{u}=A_{u}-A_{u}{j}*A_{i}
             }
         } // RREF
-        let v = (0..self.n)
-            .filter(|i| self.matrix_a.row(*i).iter().all(Zero::is_zero))
-            .collect::<Vec<_>>();
-        self = self.simplify(v); // eliminate the zero rows
+        self = self.simplify(); // eliminate the zero rows
         var_table = (0..self.m)
-            .filter(|e| !var_table.contains(&e))
+            .filter(|e| !var_table.contains(e))
             .collect::<Vec<_>>(); // get free variables table
-        var_table.iter().for_each(|x| {
-            let tmp = self.matrix_a.column(*x) * -Ratio::<T>::one();
-            self.matrix_a.column_mut(*x).copy_from(&tmp)
-        });
         let mut ans = var_table
             .iter()
-            .map(|i| self.matrix_a.column(*i).iter().cloned().collect::<Vec<_>>())
+            .map(|i| {
+                self.matrix_a
+                    .iter()
+                    .map(|row| -row[*i].clone())
+                    .collect::<Vec<_>>()
+            })
             .collect::<Vec<_>>();
         let len = var_table.len();
         for (i, j) in var_table.into_iter().enumerate() {
@@ -111,25 +102,27 @@ impl<T: CheckedType> GaussianElimination<T> {
         }
     }
 
-    fn simplify(mut self, list: Vec<usize>) -> Self {
-        for i in list.into_iter().rev() {
-            self.matrix_a = self.matrix_a.remove_row(i);
-        }
-        let (n, m) = self.matrix_a.shape();
+    fn simplify(mut self) -> Self {
+        self.matrix_a.retain(|row| !row.iter().all(Zero::is_zero));
+        let n = self.matrix_a.len();
         Self {
             matrix_a: self.matrix_a,
             n,
-            m,
+            m: self.m,
         }
     }
 
-    fn get_pivot(&self, row: usize) -> Option<usize> {
-        for column in 0..self.m {
-            if self.matrix_a[(row, column)] != Ratio::<T>::zero() {
-                return Some(column);
-            }
+    // A_{u}=A_{u}-A_{u}{j}*A_{i}
+    fn eliminate(&mut self, i: usize, u: usize, j: usize) {
+        let factor = self.matrix_a[u][j].clone();
+        let row_i = self.matrix_a[i].clone();
+        for (item, v) in self.matrix_a[u].iter_mut().zip(row_i) {
+            *item -= v * factor.clone();
         }
-        None
+    }
+
+    fn get_pivot(&self, row: usize) -> Option<usize> {
+        (0..self.m).find(|column| self.matrix_a[row][*column] != Ratio::<T>::zero())
     }
 
     fn get_leftmost_row(&self, row: usize) -> Option<usize> {
@@ -164,7 +157,7 @@ impl<T: CheckedType> GaussianElimination<T> {
     fn get_max_abs_row(&self, row: usize, column: usize) -> usize {
         let mut maxi = row;
         for k in row + 1..self.n {
-            if self.matrix_a[(k, column)].abs() > self.matrix_a[(maxi, column)].abs() {
+            if self.matrix_a[k][column].abs() > self.matrix_a[maxi][column].abs() {
                 maxi = k;
             }
         }
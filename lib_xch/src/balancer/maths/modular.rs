@@ -0,0 +1,297 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// Overall: This is the source code of the Hyper Mathlib.
+
+use num::{bigint::BigInt, rational::Ratio, Integer, One, Signed, ToPrimitive, Zero};
+// inside use(s)
+use crate::public::failures::{
+    ErrorCases,
+    ErrorCases::{Overflow, ZeroSolution},
+};
+
+// The Multi-modular Method.
+// The reduced row echelon form is unique, so every lucky prime gives the residues of the same rational entries.
+// They are combined by the Chinese Remainder Theorem, then recovered by the rational reconstruction.
+// An unlucky prime (which divides some minors) gives a smaller rank or later pivots, so it can be told and dropped.
+pub struct MultiModular {
+    matrix_a: Vec<Vec<BigInt>>, // A n*m matrix.
+    m: usize,
+}
+
+impl MultiModular {
+    pub fn new(matrix_a: Vec<Vec<BigInt>>, m: usize) -> Self {
+        // Create a MultiModular Solution.
+        Self { matrix_a, m }
+    }
+
+    pub fn solve(&self) -> Result<Vec<Vec<BigInt>>, ErrorCases> {
+        let mut pivots = Vec::<usize>::new();
+        let mut residues = Vec::<Vec<BigInt>>::new(); // The entries of the pivot rows on the free columns
+        let mut modulus = BigInt::zero(); // Zero until the first prime
+        for p in Primes::new() {
+            let (new_pivots, rows) = self.reduce(p);
+            if new_pivots.len() == self.m {
+                // Even the lucky primes can't give a larger rank.
+                return Err(ZeroSolution);
+            }
+            let free = self.free_columns(&new_pivots);
+            let new_residues = rows
+                .iter()
+                .map(|row| {
+                    free.iter()
+                        .map(|j| BigInt::from(row[*j]))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            if modulus.is_zero() || luckier(&new_pivots, &pivots) {
+                pivots = new_pivots;
+                residues = new_residues;
+                modulus = BigInt::from(p);
+            } else if new_pivots == pivots {
+                let inv = pow_mod(residue(&modulus, p), p - 2, p);
+                for (row, new_row) in residues.iter_mut().zip(new_residues.iter()) {
+                    for (x, y) in row.iter_mut().zip(new_row.iter()) {
+                        *x = crt(x, &modulus, residue(y, p), p, inv);
+                    }
+                }
+                modulus *= p;
+            } else {
+                continue; // An unlucky prime
+            }
+            if let Some(basis) = self.reconstruct(&pivots, &residues, &modulus) {
+                if self.verify(&basis) {
+                    return Ok(basis);
+                }
+            }
+        }
+        Err(Overflow) // The entries are too large to be reconstructed
+    }
+
+    // The reduced row echelon form modulo `p`. It returns the pivot columns and the pivot rows.
+    fn reduce(&self, p: u64) -> (Vec<usize>, Vec<Vec<u64>>) {
+        let mut matrix = self
+            .matrix_a
+            .iter()
+            .map(|row| row.iter().map(|x| residue(x, p)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let n = matrix.len();
+        let mut pivots = Vec::<usize>::new();
+        for j in 0..self.m {
+            let r = pivots.len();
+            let s = match (r..n).find(|i| matrix[*i][j] != 0) {
+                Some(s) => s,
+                None => continue,
+            };
+            matrix.swap(r, s);
+            let inv = pow_mod(matrix[r][j], p - 2, p);
+            for x in matrix[r].iter_mut() {
+                *x = *x * inv % p;
+            }
+            let pivot_row = matrix[r].clone();
+            for u in (0..n).filter(|u| *u != r) {
+                let factor = p - matrix[u][j];
+                for (x, y) in matrix[u].iter_mut().zip(pivot_row.iter()) {
+                    *x = (*x + factor * y) % p;
+                }
+            }
+            pivots.push(j);
+        }
+        matrix.truncate(pivots.len());
+        (pivots, matrix)
+    }
+
+    fn free_columns(&self, pivots: &[usize]) -> Vec<usize> {
+        (0..self.m)
+            .filter(|j| !pivots.contains(j))
+            .collect::<Vec<_>>()
+    }
+
+    // x_{free}=1, x_{pivot}=-R_{i}{free}, then scale it by the LCM of the denominators.
+    fn reconstruct(
+        &self,
+        pivots: &[usize],
+        residues: &[Vec<BigInt>],
+        modulus: &BigInt,
+    ) -> Option<Vec<Vec<BigInt>>> {
+        let ratios = residues
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|x| rational(x, modulus))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let basis = self
+            .free_columns(pivots)
+            .into_iter()
+            .enumerate()
+            .map(|(k, f)| {
+                let mut v = vec![Ratio::<BigInt>::zero(); self.m];
+                v[f] = Ratio::<BigInt>::one();
+                for (i, p) in pivots.iter().enumerate() {
+                    v[*p] = -ratios[i][k].clone();
+                }
+                let lcm = v
+                    .iter()
+                    .fold(BigInt::one(), |lcm, ratio| lcm.lcm(ratio.denom()));
+                v.into_iter()
+                    .map(|ratio| lcm.clone() / ratio.denom() * ratio.numer())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        Some(basis)
+    }
+
+    // Check `A * x = 0` on the original matrix.
+    fn verify(&self, basis: &[Vec<BigInt>]) -> bool {
+        basis.iter().all(|x| {
+            self.matrix_a.iter().all(|row| {
+                row.iter()
+                    .zip(x.iter())
+                    .fold(BigInt::zero(), |sum, (a, b)| sum + a * b)
+                    .is_zero()
+            })
+        })
+    }
+}
+
+// Whether the pivots are given by a luckier prime, which has a larger rank or earlier pivots.
+fn luckier(pivots: &[usize], old: &[usize]) -> bool {
+    pivots.len() > old.len() || (pivots.len() == old.len() && pivots < old)
+}
+
+// `x (mod p)` in `[0, p)`
+fn residue(x: &BigInt, p: u64) -> u64 {
+    x.mod_floor(&BigInt::from(p)).to_u64().unwrap_or(0)
+}
+
+fn pow_mod(mut base: u64, mut exp: u64, p: u64) -> u64 {
+    let mut ans = 1;
+    base %= p;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            ans = ans * base % p;
+        }
+        base = base * base % p;
+        exp >>= 1;
+    }
+    ans
+}
+
+// Find `x` such that `x = a (mod modulus)` and `x = b (mod p)`, where `inv` is the inverse of `modulus` modulo `p`.
+fn crt(a: &BigInt, modulus: &BigInt, b: u64, p: u64, inv: u64) -> BigInt {
+    let t = (b + p - residue(a, p)) % p * inv % p;
+    a + modulus * BigInt::from(t)
+}
+
+// Find `n / d` such that `n = u * d (mod modulus)` and `|n|, |d| <= sqrt(modulus / 2)`.
+fn rational(u: &BigInt, modulus: &BigInt) -> Option<Ratio<BigInt>> {
+    let bound = (modulus / BigInt::from(2)).sqrt();
+    let (mut r0, mut r1) = (modulus.clone(), u.clone());
+    let (mut t0, mut t1) = (BigInt::zero(), BigInt::one());
+    while r1 > bound {
+        let q = &r0 / &r1;
+        let r2 = &r0 - &q * &r1;
+        r0 = std::mem::replace(&mut r1, r2);
+        let t2 = &t0 - &q * &t1;
+        t0 = std::mem::replace(&mut t1, t2);
+    }
+    if t1.is_zero() || t1.abs() > bound || !r1.gcd(&t1).is_one() {
+        None
+    } else {
+        Some(Ratio::new(r1, t1))
+    }
+}
+
+// The primes below 2^31 in descending order, so that the product of two residues fits in `u64`.
+struct Primes {
+    next: u64,
+}
+
+impl Primes {
+    fn new() -> Self {
+        Self {
+            next: (1 << 31) - 1,
+        }
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        while self.next > 2 {
+            let p = self.next;
+            self.next -= 2;
+            if (3..)
+                .step_by(2)
+                .take_while(|d| d * d <= p)
+                .all(|d| !p.is_multiple_of(d))
+            {
+                return Some(p);
+            }
+        }
+        None
+    }
+}
+
+// unit tests
+#[cfg(test)]
+mod tests {
+    use super::{MultiModular, Primes};
+    use num::bigint::BigInt;
+
+    fn to_bigint(v: Vec<Vec<i64>>) -> Vec<Vec<BigInt>> {
+        v.into_iter()
+            .map(|row| row.into_iter().map(BigInt::from).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn primes() {
+        assert_eq!(
+            Primes::new().take(3).collect::<Vec<_>>(),
+            vec![2_147_483_647, 2_147_483_629, 2_147_483_587]
+        );
+    }
+
+    #[test]
+    fn solve() {
+        // KClO3+HCl=KCl+ClO2+Cl2+H2O
+        let matrix = vec![
+            vec![1, 0, -1, 0, 0, 0],
+            vec![1, 1, -1, -1, -2, 0],
+            vec![3, 0, 0, -2, 0, -1],
+            vec![0, 1, 0, 0, 0, -2],
+        ];
+        assert_eq!(
+            MultiModular::new(to_bigint(matrix), 6).solve().unwrap(),
+            to_bigint(vec![vec![-4, 0, -4, -6, 3, 0], vec![5, 6, 5, 6, 0, 3]])
+        );
+    }
+
+    #[test]
+    fn multiple_primes() {
+        // (A32767)4=A, whose coefficient is larger than every prime.
+        let big = 32767_i64.pow(4);
+        assert_eq!(
+            MultiModular::new(to_bigint(vec![vec![big, -1]]), 2)
+                .solve()
+                .unwrap(),
+            to_bigint(vec![vec![1, big]])
+        );
+    }
+}
@@ -0,0 +1,313 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// Overall: This is the source code of the Hyper Mathlib.
+
+// inside use(s)
+use super::{reduced_basis, safe_lcm};
+use crate::public::{
+    calc::{safe_calc, Operator},
+    failures::{
+        ErrorCases,
+        ErrorCases::{NoPositiveSolution, SearchLimit, ZeroSolution},
+    },
+    traits::{CheckedCalc, CheckedType},
+};
+
+// The maximum number of candidates to be checked
+const SEARCH_LIMIT: usize = 1 << 20;
+
+// Every integer solution `x` can be written as `x = (lambda_1 * w_1 + ... + lambda_k * w_k) / m`,
+// where `lambda_i` is the value of the column which only the basic solution `i` touches.
+// So we can search for the smallest positive solution by enumerating `lambda`.
+pub struct MinimalPositive<T: CheckedType + CheckedCalc> {
+    weights: Vec<Vec<T>>, // A k*n matrix.
+    m: T,
+    k: usize,
+    n: usize,
+    best: Option<(T, Vec<T>)>, // A known positive solution and the sum of it
+}
+
+impl<T: CheckedType + CheckedCalc> MinimalPositive<T> {
+    pub fn new(basis: &[Vec<T>]) -> Result<Self, ErrorCases> {
+        let (basis, columns) = reduced_basis(basis)?;
+        let k = basis.len();
+        if k == 0 {
+            return Err(ZeroSolution);
+        }
+        let n = basis[0].len();
+        // the value of the column which only the basic solution `i` touches
+        let denoms = columns
+            .into_iter()
+            .zip(basis.iter())
+            .map(|(j, b)| b[j].clone())
+            .collect::<Vec<_>>();
+        let m = denoms
+            .iter()
+            .try_fold(T::one(), |lcm, d| safe_lcm(&lcm, d))?;
+        let mut weights = Vec::<Vec<T>>::with_capacity(k);
+        for (b, d) in basis.iter().zip(denoms.iter()) {
+            let factor = safe_calc(&m, d, &Operator::Div)?;
+            weights.push(
+                b.iter()
+                    .map(|x| safe_calc(x, &factor, &Operator::Mul))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+        Ok(Self {
+            weights,
+            m,
+            k,
+            n,
+            best: None,
+        })
+    }
+
+    // Give a known positive solution, so that the search stops as soon as nothing smaller can be found.
+    pub fn bound(mut self, x: Vec<T>) -> Result<Self, ErrorCases> {
+        let total = x
+            .iter()
+            .try_fold(T::zero(), |sum, v| safe_calc(&sum, v, &Operator::Add))?;
+        self.best = Some((total, x));
+        Ok(self)
+    }
+
+    pub fn solve(mut self) -> Result<Vec<T>, ErrorCases> {
+        // If every weight of a column is not positive, the column can never be positive.
+        if (0..self.n).any(|j| self.weights.iter().all(|w| !w[j].is_positive())) {
+            return Err(NoPositiveSolution);
+        }
+        let mut best = self.best.take();
+        let mut count = 0;
+        let mut s = T::zero();
+        for _ in 0..self.k {
+            s = safe_calc(&s, &T::one(), &Operator::Add)?;
+        }
+        let mut rest = T::zero(); // The lower bound of the sum of the other columns
+        for _ in self.k..self.n {
+            rest = safe_calc(&rest, &T::one(), &Operator::Add)?;
+        }
+        loop {
+            if let Some((total, _)) = &best {
+                if safe_calc(&s, &rest, &Operator::Add)? >= *total {
+                    break;
+                }
+            }
+            let mut lambda = Vec::<T>::with_capacity(self.k);
+            let partial = vec![T::zero(); self.n];
+            self.search(s.clone(), &mut lambda, &partial, &mut best, &mut count)?;
+            if count > SEARCH_LIMIT {
+                return Err(SearchLimit);
+            }
+            s = safe_calc(&s, &T::one(), &Operator::Add)?;
+        }
+        best.map(|(_, x)| x).ok_or(NoPositiveSolution)
+    }
+
+    // Enumerate all the `lambda` whose entries are positive and sum up to `remaining`.
+    // `partial` is `lambda_1 * w_1 + ... + lambda_t * w_t` of the entries which are already chosen.
+    fn search(
+        &self,
+        remaining: T,
+        lambda: &mut Vec<T>,
+        partial: &[T],
+        best: &mut Option<(T, Vec<T>)>,
+        count: &mut usize,
+    ) -> Result<(), ErrorCases> {
+        *count += 1;
+        if *count > SEARCH_LIMIT || self.prune(lambda.len(), &remaining, partial, best)? {
+            return Ok(());
+        }
+        if lambda.len() == self.k - 1 {
+            lambda.push(remaining);
+            if let Some(x) = self.evaluate(lambda)? {
+                let total = x
+                    .iter()
+                    .try_fold(T::zero(), |sum, v| safe_calc(&sum, v, &Operator::Add))?;
+                if best.as_ref().is_none_or(|(b, _)| total < *b) {
+                    *best = Some((total, x));
+                }
+            }
+            lambda.pop();
+            return Ok(());
+        }
+        // Leave at least one for each of the following entries.
+        let mut left = remaining.clone();
+        for _ in lambda.len()..self.k - 1 {
+            left = safe_calc(&left, &T::one(), &Operator::Sub)?;
+        }
+        let w = &self.weights[lambda.len()];
+        let mut v = T::one();
+        while v <= left {
+            let next = partial
+                .iter()
+                .zip(w.iter())
+                .map(|(p, x)| safe_calc(p, &safe_calc(&v, x, &Operator::Mul)?, &Operator::Add))
+                .collect::<Result<Vec<_>, _>>()?;
+            lambda.push(v.clone());
+            self.search(
+                safe_calc(&remaining, &v, &Operator::Sub)?,
+                lambda,
+                &next,
+                best,
+                count,
+            )?;
+            lambda.pop();
+            v = safe_calc(&v, &T::one(), &Operator::Add)?;
+        }
+        Ok(())
+    }
+
+    // Whether no choice of the entries from `t` on, which are positive and sum up to `remaining`,
+    // gives a positive solution smaller than the best one.
+    // Every entry is at least one, and the rest of `remaining` can go to any of them,
+    // which bounds the value of each column from both sides.
+    fn prune(
+        &self,
+        t: usize,
+        remaining: &T,
+        partial: &[T],
+        best: &Option<(T, Vec<T>)>,
+    ) -> Result<bool, ErrorCases> {
+        let mut extra = remaining.clone();
+        for _ in t..self.k {
+            extra = safe_calc(&extra, &T::one(), &Operator::Sub)?;
+        }
+        let mut lower = T::zero(); // The lower bound of the sum of the solution
+        for (j, p) in partial.iter().enumerate() {
+            let (mut low, mut high) = (p.clone(), p.clone());
+            let (mut min, mut max) = (T::zero(), T::zero());
+            for (i, w) in self.weights[t..].iter().enumerate() {
+                low = safe_calc(&low, &w[j], &Operator::Add)?;
+                high = safe_calc(&high, &w[j], &Operator::Add)?;
+                if i == 0 || w[j] < min {
+                    min = w[j].clone();
+                }
+                if i == 0 || w[j] > max {
+                    max = w[j].clone();
+                }
+            }
+            low = safe_calc(
+                &low,
+                &safe_calc(&extra, &min, &Operator::Mul)?,
+                &Operator::Add,
+            )?;
+            high = safe_calc(
+                &high,
+                &safe_calc(&extra, &max, &Operator::Mul)?,
+                &Operator::Add,
+            )?;
+            // The column is `value / m`, which must be at least one.
+            if high < self.m {
+                return Ok(true);
+            }
+            let column = if low > self.m {
+                let ceil = safe_calc(
+                    &safe_calc(&low, &self.m, &Operator::Add)?,
+                    &T::one(),
+                    &Operator::Sub,
+                )?;
+                safe_calc(&ceil, &self.m, &Operator::Div)?
+            } else {
+                T::one()
+            };
+            lower = safe_calc(&lower, &column, &Operator::Add)?;
+        }
+        Ok(best.as_ref().is_some_and(|(total, _)| lower >= *total))
+    }
+
+    // Returns the solution if it is a positive integer solution.
+    fn evaluate(&self, lambda: &[T]) -> Result<Option<Vec<T>>, ErrorCases> {
+        let mut x = Vec::<T>::with_capacity(self.n);
+        for j in 0..self.n {
+            let mut sum = T::zero();
+            for (l, w) in lambda.iter().zip(self.weights.iter()) {
+                sum = safe_calc(&sum, &safe_calc(l, &w[j], &Operator::Mul)?, &Operator::Add)?;
+            }
+            if !sum.is_positive() || !safe_calc(&sum, &self.m, &Operator::Rem)?.is_zero() {
+                return Ok(None);
+            }
+            x.push(safe_calc(&sum, &self.m, &Operator::Div)?);
+        }
+        Ok(Some(x))
+    }
+}
+
+// unit tests
+#[cfg(test)]
+mod tests {
+    use super::MinimalPositive;
+    use crate::public::failures::ErrorCases;
+
+    #[test]
+    fn single() {
+        let basis = vec![vec![-2, -2, -1]];
+        assert_eq!(
+            MinimalPositive::<i32>::new(&basis).unwrap().solve(),
+            Ok(vec![2, 2, 1])
+        );
+        let basis = vec![vec![-1, 1, 0]];
+        assert_eq!(
+            MinimalPositive::<i32>::new(&basis).unwrap().solve(),
+            Err(ErrorCases::NoPositiveSolution)
+        );
+    }
+
+    #[test]
+    fn combination() {
+        // KClO3+HCl=KCl+ClO2+Cl2+H2O
+        let basis = vec![vec![-4, 0, -4, -6, 3, 0], vec![5, 6, 5, 6, 0, 3]];
+        assert_eq!(
+            MinimalPositive::<i32>::new(&basis).unwrap().solve(),
+            Ok(vec![2, 4, 2, 2, 1, 2])
+        );
+    }
+
+    #[test]
+    fn unreduced() {
+        // The sum and the difference of the basis above, so no column is touched by only one of them
+        let basis = vec![vec![1, 6, 1, 0, 3, 3], vec![-9, -6, -9, -12, 3, -3]];
+        assert_eq!(
+            MinimalPositive::<i32>::new(&basis).unwrap().solve(),
+            Ok(vec![2, 4, 2, 2, 1, 2])
+        );
+    }
+
+    #[test]
+    fn wide() {
+        // `A+B+C+D+E=AB+AC+...+DE` without a known solution, one basic solution for each product
+        let n = 5;
+        let pairs = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect::<Vec<_>>();
+        let basis = pairs
+            .iter()
+            .enumerate()
+            .map(|(p, (i, j))| {
+                let mut b = vec![0; n + pairs.len()];
+                b[*i] = 1;
+                b[*j] = 1;
+                b[n + p] = 1;
+                b
+            })
+            .collect::<Vec<_>>();
+        let mut expected = vec![4; n];
+        expected.extend(vec![1; pairs.len()]);
+        assert_eq!(
+            MinimalPositive::<i32>::new(&basis).unwrap().solve(),
+            Ok(expected)
+        );
+    }
+}
@@ -18,9 +18,18 @@
 //!
 //! It can parse and balance *the Chemical Equation*.
 //!
-//! -  Unlimited brackets
-//! -  No Periodic table of the elements needed
+//! -  Brackets `()`, `[]` and `{}`, nested up to 64 levels
+//! -  No Periodic table of the elements needed, though the symbols can be checked against it with `Handler::validate`
 //! -  Provides the set of Basic Solutions instead of only one solution.
+//! -  Accepts `=`, `->`, `→`, `<->`, `<=>` and `⇌` as the arrow.
+//! -  Recognises the physical state annotations `(s)`, `(l)`, `(g)` and `(aq)`.
+//! -  Charges as `<2e+>`, `^2+`, `^{2+}`, `{2+}`, `+2`, `²⁺`, `3-` after a closing bracket, or a bare sign like `Na+(aq)`.
+//! -  Isotopes (`[13C]`, `^2H`) and nuclear reactions (`U235+n=Ba141+Kr92+3n`).
+//! -  Unicode subscripts (`H₂O`) and the hydrate dots `.`, `·`, `•`, `∙` and `⋅`.
+//! -  Fractional subscripts (`Fe0.95O`, `LiNi1/3Mn1/3Co1/3O2`), while the coefficients are still integers.
+//! -  Molar masses and percent compositions (`CuSO4.5H2O`) from the standard atomic weights.
+//! -  Checks the coefficients of a balanced equation element by element with `handler::verify`, or the ones written in it (`2H2+O2=2H2O`) with `handler::check`.
+//! -  Supports arbitrary-precision integers (`num::BigInt`) with the `bigint` feature, which only enables this integer type (`Modular` uses `num::BigInt` internally either way).
 //!
 //! # Getting Started
 //!
@@ -19,4 +19,6 @@
 mod ast;
 mod atomdict;
 mod datastructure;
+pub mod elements;
+mod error;
 pub mod handler;
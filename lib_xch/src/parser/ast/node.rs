@@ -15,25 +15,28 @@
 
 // Overall: This is the source code of the Delta-3 Parser.
 
-use crate::public::failures::ErrorCases;
 use id_tree::{NodeId, Tree};
+use num::rational::Ratio;
 // inside use(s)
 use self::NodeType::{Atom, Molecule, MoleculeGroup, ParenthesisWrapper};
 use super::{super::atomdict::AtomDict, treebuilder::F};
-use crate::public::traits::CheckedType;
+use crate::public::{
+    failures::ErrorCases,
+    traits::{CheckedCalc, CheckedType},
+};
 
 pub enum NodeType<T: CheckedType> {
-    Atom(String, T),       // `Symbol` and `Suffix_Operand`
-    ParenthesisWrapper(T), // `Suffix_Operand`
-    Molecule(T, T),        // `Prefix_Operand` and `Electronic_Charge`
-    MoleculeGroup,         // No Attributes contained
+    Atom(String, Ratio<T>),       // `Symbol` and `Suffix_Operand`
+    ParenthesisWrapper(Ratio<T>), // `Suffix_Operand`
+    Molecule(Ratio<T>, T),        // `Prefix_Operand` and `Electronic_Charge`
+    MoleculeGroup,                // No Attributes contained
 }
 
 pub struct ASTNode<T: CheckedType> {
     nodetype: NodeType<T>,
 }
 
-impl<T: CheckedType> ASTNode<T> {
+impl<T: CheckedType + CheckedCalc> ASTNode<T> {
     pub fn new(nodetype: NodeType<T>) -> Self {
         Self { nodetype }
     }
@@ -46,28 +49,28 @@ impl<T: CheckedType> ASTNode<T> {
         match &self.nodetype {
             Atom(s, o) => {
                 let mut a = AtomDict::<T>::new();
-                a.insert(s.to_string(), *o);
+                a.insert(s.to_string(), o.clone());
                 Ok(a)
             }
             Molecule(o, c) => {
-                let mut a = AtomDict::<T>::new();
-                a.insert("e".to_string(), *c);
-                Ok(tree.children_ids(node_id).map_err(F)?.fold(a, |b, c| {
-                    b + tree.get(c).unwrap().data().to_atomdict(c, tree).unwrap()
-                }) * *o)
-            }
-            ParenthesisWrapper(o) => {
-                let a = AtomDict::<T>::new();
-                Ok(tree.children_ids(node_id).map_err(F)?.fold(a, |b, c| {
-                    b + tree.get(c).unwrap().data().to_atomdict(c, tree).unwrap()
-                }) * *o)
-            }
-            MoleculeGroup => {
-                let a = AtomDict::<T>::new();
-                Ok(tree.children_ids(node_id).map_err(F)?.fold(a, |b, c| {
-                    b + tree.get(c).unwrap().data().to_atomdict(c, tree).unwrap()
-                }))
+                let mut charge = AtomDict::<T>::new();
+                charge.insert("e".to_string(), Ratio::from_integer(c.clone()));
+                children(node_id, tree)?.checked_add(charge)?.checked_mul(o)
             }
+            ParenthesisWrapper(o) => children(node_id, tree)?.checked_mul(o),
+            MoleculeGroup => children(node_id, tree),
         }
     }
 }
+
+// The sum of the children
+fn children<T: CheckedType + CheckedCalc>(
+    node_id: &NodeId,
+    tree: &Tree<ASTNode<T>>,
+) -> Result<AtomDict<T>, ErrorCases> {
+    tree.children_ids(node_id)
+        .map_err(F)?
+        .try_fold(AtomDict::<T>::new(), |a, c| {
+            a.checked_add(tree.get(c).map_err(F)?.data().to_atomdict(c, tree)?)
+        })
+}
@@ -20,8 +20,16 @@ use id_tree::{
     Node, NodeId, Tree, TreeBuilder,
 };
 // inside use(s)
-use super::{super::atomdict::AtomDict, node::ASTNode, node::NodeType, treebuilder::F};
-use crate::public::{failures::ErrorCases, traits::CheckedType};
+use super::{
+    super::{atomdict::AtomDict, error::internal},
+    node::ASTNode,
+    node::NodeType,
+    treebuilder::F,
+};
+use crate::public::{
+    failures::ErrorCases,
+    traits::{CheckedCalc, CheckedType},
+};
 
 pub struct ASTTree<T: CheckedType> {
     tree: Tree<ASTNode<T>>,
@@ -29,19 +37,17 @@ pub struct ASTTree<T: CheckedType> {
     index: usize,
 }
 
-impl<T: CheckedType> ASTTree<T> {
-    pub fn new() -> Self {
+impl<T: CheckedType + CheckedCalc> ASTTree<T> {
+    pub fn new() -> Result<Self, ErrorCases> {
         let mut tree: Tree<ASTNode<T>> = TreeBuilder::new().build();
-        let mut nodes: Vec<NodeId> = Vec::new();
-        nodes.push(
-            tree.insert(Node::new(ASTNode::new(NodeType::MoleculeGroup)), AsRoot)
-                .unwrap(), // By using `AsRoot`, it always returns `Ok(_)`, so it's safe.
-        );
-        Self {
+        let nodes: Vec<NodeId> = vec![tree
+            .insert(Node::new(ASTNode::new(NodeType::MoleculeGroup)), AsRoot)
+            .map_err(F)?];
+        Ok(Self {
             tree,
             nodes,
             index: 0,
-        }
+        })
     }
 
     pub fn get_index(&self) -> usize {
@@ -65,10 +71,14 @@ impl<T: CheckedType> ASTTree<T> {
     }
 
     pub fn to_atomdict(&self) -> Result<AtomDict<T>, ErrorCases> {
+        let root = self
+            .tree
+            .root_node_id()
+            .ok_or_else(|| internal("[AST] No root node"))?;
         self.tree
-            .get(&self.tree.root_node_id().unwrap())
+            .get(root)
             .map_err(F)?
             .data()
-            .to_atomdict(&self.tree.root_node_id().unwrap(), &self.tree)
+            .to_atomdict(root, &self.tree)
     }
 }
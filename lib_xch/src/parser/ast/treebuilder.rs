@@ -16,95 +16,123 @@
 // Overall: This is the source code of the Delta-3 Parser.
 
 use id_tree::NodeIdError;
-use pest::{iterators::Pair, Parser};
-use pest_derive::Parser;
+use num::rational::Ratio;
+use pest::iterators::Pair;
 use std::str::FromStr;
 // inside use(s)
-use super::{node::NodeType, tree::ASTTree};
-use crate::public::{failures::ErrorCases, traits::CheckedType};
+use super::{
+    super::{
+        atomdict::reduced,
+        elements::isotope_name,
+        error::{error, internal, Source},
+        handler::Rule,
+    },
+    node::NodeType,
+    tree::ASTTree,
+};
+use crate::public::{
+    failures::ErrorCases,
+    traits::{CheckedCalc, CheckedType},
+};
 
-pub const F: fn(NodeIdError) -> ErrorCases =
-    |_| ErrorCases::ParserError("[Internal] [AST] NodeId Error".to_string());
+pub const F: fn(NodeIdError) -> ErrorCases = |_| internal("[AST] NodeId Error");
 
-#[derive(Parser)]
-#[grammar = "ast.pest"]
-struct MoleculeParser;
+// The superscript and subscript digits from zero to nine
+const SUPERSCRIPTS: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹";
+const SUBSCRIPTS: &str = "₀₁₂₃₄₅₆₇₈₉";
 
-pub struct ASTTreeBuilder;
+pub struct ASTTreeBuilder {
+    isotopes: bool,
+}
 
 impl ASTTreeBuilder {
     pub fn new() -> Self {
-        Self {}
+        Self { isotopes: false }
     }
 
-    pub fn parse<T: CheckedType>(&self, formula: &str) -> Result<ASTTree<T>, ErrorCases> {
-        let mut tree = ASTTree::<T>::new();
-        let pairs = MoleculeParser::parse(Rule::molecule_group, formula)
-            .map_err(|e| ErrorCases::ParserError(e.to_string()))?;
-        for p in pairs {
-            // The `pairs` only contains one Pair actually.
-            self.build_tree(p, &mut tree)?
-        }
+    // Read `D` and `T` as `[2H]` and `[3H]`. Without the periodic table, they are symbols like any other.
+    pub fn isotopes(mut self, isotopes: bool) -> Self {
+        self.isotopes = isotopes;
+        self
+    }
+
+    // Build the tree from the `molecule_group` pair of the equation, so that it is parsed only once.
+    pub fn build<T: CheckedType + CheckedCalc>(
+        &self,
+        pair: Pair<'_, Rule>,
+        source: &Source<'_>,
+    ) -> Result<ASTTree<T>, ErrorCases> {
+        let mut tree = ASTTree::<T>::new()?;
+        self.build_tree(pair, &mut tree, source)?;
         Ok(tree)
     }
 
-    fn build_tree<T: CheckedType>(
+    // Build the tree like `build`, but the leading number of the first molecule is taken out
+    // as the coefficient of the whole chemical formula, so `2CuSO4.5H2O` is two of `CuSO4.5H2O`.
+    pub fn build_with_coefficient<T: CheckedType + CheckedCalc>(
+        &self,
+        pair: Pair<'_, Rule>,
+        source: &Source<'_>,
+    ) -> Result<(T, ASTTree<T>), ErrorCases> {
+        let mut tree = ASTTree::<T>::new()?;
+        let mut coefficient = T::one();
+        for (i, p) in pair.into_inner().enumerate() {
+            tree.change_index(0);
+            if i == 0 {
+                let prefix = p
+                    .clone()
+                    .into_inner()
+                    .next()
+                    .ok_or_else(|| internal("Empty molecule"))?;
+                let operand = self.build_molecule(p, &mut tree, source, true)?;
+                if !operand.is_integer() {
+                    return Err(error(
+                        source.span(&prefix),
+                        &[],
+                        Some(prefix.as_str().to_string()),
+                        Some("The coefficients must be integers".to_string()),
+                    ));
+                }
+                coefficient = operand.to_integer();
+            } else {
+                self.build_tree(p, &mut tree, source)?;
+            }
+        }
+        Ok((coefficient, tree))
+    }
+
+    fn build_tree<T: CheckedType + CheckedCalc>(
         &self,
         pair: Pair<'_, Rule>,
         tree: &mut ASTTree<T>,
+        source: &Source<'_>,
     ) -> Result<(), ErrorCases> {
         match pair.as_rule() {
             Rule::atom => {
                 let pairs = pair.into_inner().collect::<Vec<_>>();
-                let atom_name = pairs[0].as_str().to_string();
-                let operand = if pairs[pairs.len() - 1].as_rule() == Rule::num {
-                    self.parse_from(&pairs[pairs.len() - 1].as_str())?
+                let atom_name = if pairs[0].as_rule() == Rule::isotope {
+                    // Both `[13C]` and `^13C` are stored as `[13C]`.
+                    let inner_pairs = pairs[0].clone().into_inner().collect::<Vec<_>>();
+                    format!("[{}{}]", inner_pairs[0].as_str(), inner_pairs[1].as_str())
                 } else {
-                    T::one()
+                    // `D` and `T` are the same as `[2H]` and `[3H]`.
+                    self.isotopes
+                        .then(|| isotope_name(pairs[0].as_str()))
+                        .flatten()
+                        .unwrap_or_else(|| pairs[0].as_str().to_string())
                 };
+                let operand = parse_operand(&pairs[pairs.len() - 1], source)?;
                 self.new_node_alias(tree, NodeType::Atom(atom_name, operand))?;
                 Ok(())
             }
-            Rule::molecule => {
-                let mut pairs = pair.into_inner().collect::<Vec<_>>();
-                let prefix = if pairs[0].as_rule() == Rule::num {
-                    self.parse_from(pairs[0].as_str())?
-                } else {
-                    T::one()
-                };
-                let charge = if pairs[pairs.len() - 1].as_rule() == Rule::electron {
-                    let inner_pairs = pairs.pop().unwrap().into_inner().collect::<Vec<_>>();
-                    let operand = if inner_pairs[0].as_rule() == Rule::num {
-                        self.parse_from(inner_pairs[0].as_str())?
-                    } else {
-                        T::one()
-                    };
-                    self.parse_from(&format!(
-                        "{}{}",
-                        inner_pairs[inner_pairs.len() - 1].as_str(),
-                        operand,
-                    ))?
-                } else {
-                    T::zero()
-                };
-                let index = self.new_node_alias(tree, NodeType::Molecule(prefix, charge))?;
-                for p in pairs {
-                    tree.change_index(index);
-                    self.build_tree(p, tree)?;
-                }
-                Ok(())
-            }
+            Rule::molecule => self.build_molecule(pair, tree, source, false).map(|_| ()),
             Rule::parenthesis_wrapper => {
                 let pairs = pair.into_inner().collect::<Vec<_>>();
-                let suffix = if pairs[pairs.len() - 1].as_rule() == Rule::num {
-                    self.parse_from(pairs[pairs.len() - 1].as_str())?
-                } else {
-                    T::one()
-                };
+                let suffix = parse_operand(&pairs[pairs.len() - 1], source)?;
                 let index = self.new_node_alias(tree, NodeType::ParenthesisWrapper(suffix))?;
                 for p in pairs {
                     tree.change_index(index);
-                    self.build_tree(p, tree)?;
+                    self.build_tree(p, tree, source)?;
                 }
                 Ok(())
             }
@@ -113,7 +141,7 @@ impl ASTTreeBuilder {
                 let index = tree.get_index();
                 for p in pairs {
                     tree.change_index(index);
-                    self.build_tree(p, tree)?;
+                    self.build_tree(p, tree, source)?;
                 }
                 Ok(())
             }
@@ -121,46 +149,199 @@ impl ASTTreeBuilder {
         }
     }
 
-    fn parse_from<T: FromStr>(&self, s: &str) -> Result<T, ErrorCases> {
-        s.parse::<T>()
-            .map_err(|_| ErrorCases::ParserError(format!("Can't parse '{}'", s)))
+    // Returns the leading number of the molecule. With `as_coefficient`, it is left out of the molecule.
+    fn build_molecule<T: CheckedType + CheckedCalc>(
+        &self,
+        pair: Pair<'_, Rule>,
+        tree: &mut ASTTree<T>,
+        source: &Source<'_>,
+        as_coefficient: bool,
+    ) -> Result<Ratio<T>, ErrorCases> {
+        let mut pairs = pair.into_inner().collect::<Vec<_>>();
+        let prefix = parse_operand(&pairs[0], source)?;
+        let charge = if pairs[pairs.len() - 1].as_rule() == Rule::charge {
+            let charge = pairs.remove(pairs.len() - 1);
+            self.parse_charge(charge)?
+        } else {
+            T::zero()
+        };
+        let operand = if as_coefficient {
+            Ratio::from_integer(T::one())
+        } else {
+            prefix.clone()
+        };
+        let index = self.new_node_alias(tree, NodeType::Molecule(operand, charge))?;
+        for p in pairs {
+            tree.change_index(index);
+            self.build_tree(p, tree, source)?;
+        }
+        Ok(prefix)
     }
 
-    fn new_node_alias<T: CheckedType>(
+    // All the notations of the charge (`<2e+>`, `^2+`, `^{2+}`, `{2+}`, `+2`, `2+` and `²⁺`) are the same.
+    fn parse_charge<T: CheckedType + CheckedCalc>(
+        &self,
+        pair: Pair<'_, Rule>,
+    ) -> Result<T, ErrorCases> {
+        let mut sign = "+";
+        let mut operand = "1".to_string();
+        for p in pair.into_inner().flatten() {
+            match p.as_rule() {
+                Rule::sign => sign = p.as_str(),
+                Rule::sup_sign if p.as_str() == "⁻" => sign = "-",
+                Rule::num | Rule::sup_num => operand = p.as_str().to_string(),
+                _ => (),
+            }
+        }
+        parse_from(&format!("{}{}", sign, operand)).ok_or(ErrorCases::Overflow)
+    }
+
+    fn new_node_alias<T: CheckedType + CheckedCalc>(
         &self,
         tree: &mut ASTTree<T>,
         nodetype: NodeType<T>,
     ) -> Result<usize, ErrorCases> {
-        Ok(tree.new_node(nodetype)?)
+        tree.new_node(nodetype)
+    }
+}
+
+// Parse the pair as an operand if it is a number, otherwise the operand is one.
+fn parse_operand<T: CheckedType + CheckedCalc>(
+    pair: &Pair<'_, Rule>,
+    source: &Source<'_>,
+) -> Result<Ratio<T>, ErrorCases> {
+    let s = pair.as_str();
+    let span = source.span(pair);
+    match pair.as_rule() {
+        Rule::num => Ok(Ratio::from_integer(
+            parse_from(s).ok_or(ErrorCases::Overflow)?,
+        )),
+        Rule::ratio => {
+            // Either a decimal like `0.95` or a fraction like `1/3`
+            let (numer, denom) = match s.find('/') {
+                Some(i) => (parse_from::<T>(&s[..i]), parse_from::<T>(&s[i + 1..])),
+                None => {
+                    let point = s.find('.').unwrap_or(s.len());
+                    let decimals = s.len() - point - 1;
+                    (
+                        parse_from::<T>(&s.replacen('.', "", 1)),
+                        parse_from::<T>(&format!("1{}", "0".repeat(decimals))),
+                    )
+                }
+            };
+            match (numer, denom) {
+                (_, Some(ref denom)) if denom.is_zero() => Err(error(
+                    span,
+                    &[],
+                    Some(s.to_string()),
+                    Some("The denominator is zero".to_string()),
+                )),
+                (Some(numer), Some(denom)) => reduced(numer, denom),
+                _ => Err(ErrorCases::Overflow),
+            }
+        }
+        _ => Ok(Ratio::from_integer(T::one())),
     }
 }
 
+// Parse a number, which may be written in superscript or subscript digits.
+// The grammar only lets the digits through, so it fails only if the number is too large for `T`.
+pub fn parse_from<T: FromStr>(s: &str) -> Option<T> {
+    s.chars()
+        .map(|c| {
+            SUPERSCRIPTS
+                .chars()
+                .position(|s| s == c)
+                .or_else(|| SUBSCRIPTS.chars().position(|s| s == c))
+                .map_or(c.to_string(), |d| d.to_string())
+        })
+        .collect::<String>()
+        .parse::<T>()
+        .ok()
+}
+
 #[cfg(test)]
 mod tests {
-    use super::ASTTreeBuilder;
-    use std::collections::HashMap;
+    use super::{
+        super::super::{
+            error::Source,
+            handler::{EquParser, Rule},
+        },
+        ASTTreeBuilder,
+    };
+    use num::rational::Ratio;
+    use pest::Parser;
+
+    fn atoms(formula: &str) -> Vec<(String, Ratio<i32>)> {
+        let pair = EquParser::parse(Rule::molecule_group, formula)
+            .unwrap()
+            .next()
+            .unwrap();
+        ASTTreeBuilder::new()
+            .build::<i32>(pair, &Source::new(formula))
+            .unwrap()
+            .to_atomdict()
+            .unwrap()
+            .get_dict()
+            .to_vec()
+    }
 
     #[test]
     fn parse() {
-        let builder = ASTTreeBuilder::new();
         assert_eq!(
-            builder
-                .parse::<i32>("((NH4)3(PO4.12MoO3<12e->).2H2O)<32e+>") // This chemical formula is unreal
-                .unwrap()
-                .to_atomdict()
-                .unwrap()
-                .get_dict(),
-            &[
+            atoms("((NH4)3(PO4.12MoO3<12e->).2H2O)<32e+>"), // This chemical formula is unreal
+            [
+                ("N".to_string(), 3),
                 ("H".to_string(), 16),
                 ("e".to_string(), -112),
-                ("N".to_string(), 3),
-                ("O".to_string(), 42),
                 ("P".to_string(), 1),
+                ("O".to_string(), 42),
                 ("Mo".to_string(), 12)
             ]
             .iter()
-            .cloned()
-            .collect::<HashMap<String, i32>>()
+            .map(|(k, v)| (k.to_string(), Ratio::from_integer(*v)))
+            .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn fractional() {
+        assert_eq!(
+            atoms("(LiNi1/3Mn1/3Co1/3O2)0.5"),
+            [
+                ("Li".to_string(), Ratio::new(1, 2)),
+                ("Ni".to_string(), Ratio::new(1, 6)),
+                ("Mn".to_string(), Ratio::new(1, 6)),
+                ("Co".to_string(), Ratio::new(1, 6)),
+                ("O".to_string(), Ratio::from_integer(1)),
+                ("e".to_string(), Ratio::from_integer(0))
+            ]
+        );
+    }
+
+    #[test]
+    fn coefficient() {
+        let formula = "2CuSO4.5H2O";
+        let pair = EquParser::parse(Rule::molecule_group, formula)
+            .unwrap()
+            .next()
+            .unwrap();
+        let (coefficient, tree) = ASTTreeBuilder::new()
+            .build_with_coefficient::<i32>(pair, &Source::new(formula))
+            .unwrap();
+        assert_eq!(coefficient, 2);
+        assert_eq!(
+            tree.to_atomdict().unwrap().get_dict().to_vec(),
+            [
+                ("Cu".to_string(), 1),
+                ("S".to_string(), 1),
+                ("O".to_string(), 9),
+                ("e".to_string(), 0),
+                ("H".to_string(), 10)
+            ]
+            .iter()
+            .map(|(k, v)| (k.to_string(), Ratio::from_integer(*v)))
+            .collect::<Vec<_>>()
         );
     }
 }
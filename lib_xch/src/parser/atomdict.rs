@@ -15,104 +15,144 @@
 
 // Overall: This is the source code of the Delta-3 Parser.
 
-use std::{
-    collections::HashMap,
-    ops::{Add, AddAssign, Mul, MulAssign},
-};
+use num::rational::Ratio;
 // inside use(s)
-use crate::public::traits::CheckedType;
+use crate::public::{
+    calc::{safe_calc, Operator},
+    failures::ErrorCases,
+    traits::{CheckedCalc, CheckedType},
+};
 
+// Elements are kept in the order of their first appearance.
+// The subscripts may be fractional, so the numbers of atoms are rational.
 #[derive(Debug)]
-pub struct AtomDict<T: CheckedType> {
-    dict: HashMap<String, T>,
+pub struct AtomDict<T: CheckedType + CheckedCalc> {
+    dict: Vec<(String, Ratio<T>)>,
 }
 
-impl<T: CheckedType> AtomDict<T> {
+impl<T: CheckedType + CheckedCalc> AtomDict<T> {
     pub fn new() -> Self {
-        Self {
-            dict: HashMap::new(),
-        }
+        Self { dict: Vec::new() }
     }
 
-    pub fn insert(&mut self, k: String, v: T) {
-        self.dict.insert(k, v);
+    pub fn insert(&mut self, k: String, v: Ratio<T>) {
+        match self.dict.iter_mut().find(|(key, _)| *key == k) {
+            Some((_, value)) => *value = v,
+            None => self.dict.push((k, v)),
+        }
     }
 
-    pub fn get_dict(&self) -> &HashMap<String, T> {
+    pub fn get_dict(&self) -> &[(String, Ratio<T>)] {
         &self.dict
     }
-}
 
-impl<T: CheckedType> Add for AtomDict<T> {
-    type Output = Self;
-    fn add(mut self, rhs: Self) -> Self::Output {
-        self += rhs;
-        self
+    pub fn checked_add(mut self, rhs: Self) -> Result<Self, ErrorCases> {
+        for (k, v) in rhs.dict {
+            match self.dict.iter_mut().find(|(key, _)| *key == k) {
+                Some((_, value)) => *value = add(value, &v)?,
+                None => self.dict.push((k, v)),
+            }
+        }
+        Ok(self)
     }
-}
 
-impl<T: CheckedType> Mul<T> for AtomDict<T> {
-    type Output = Self;
-    fn mul(mut self, rhs: T) -> Self::Output {
-        self *= rhs;
-        self
+    pub fn checked_mul(mut self, rhs: &Ratio<T>) -> Result<Self, ErrorCases> {
+        for (_, v) in self.dict.iter_mut() {
+            *v = mul(v, rhs)?;
+        }
+        Ok(self)
     }
 }
 
-impl<T: CheckedType> AddAssign for AtomDict<T> {
-    fn add_assign(&mut self, rhs: Self) {
-        rhs.dict
-            .into_iter()
-            .for_each(|(k, v)| *self.dict.entry(k).or_insert_with(T::zero) += v);
-    }
+// The fractions are calculated by `safe_calc`, so that an overflow is reported instead of panicking.
+pub fn add<T: CheckedType + CheckedCalc>(
+    a: &Ratio<T>,
+    b: &Ratio<T>,
+) -> Result<Ratio<T>, ErrorCases> {
+    reduced(
+        safe_calc(
+            &safe_calc(a.numer(), b.denom(), &Operator::Mul)?,
+            &safe_calc(b.numer(), a.denom(), &Operator::Mul)?,
+            &Operator::Add,
+        )?,
+        safe_calc(a.denom(), b.denom(), &Operator::Mul)?,
+    )
 }
 
-impl<T: CheckedType> MulAssign<T> for AtomDict<T> {
-    fn mul_assign(&mut self, rhs: T) {
-        self.dict.iter_mut().for_each(|(_, v)| *v *= rhs);
-    }
+pub fn mul<T: CheckedType + CheckedCalc>(
+    a: &Ratio<T>,
+    b: &Ratio<T>,
+) -> Result<Ratio<T>, ErrorCases> {
+    reduced(
+        safe_calc(a.numer(), b.numer(), &Operator::Mul)?,
+        safe_calc(a.denom(), b.denom(), &Operator::Mul)?,
+    )
+}
+
+pub fn neg<T: CheckedType + CheckedCalc>(a: &Ratio<T>) -> Result<Ratio<T>, ErrorCases> {
+    Ok(Ratio::new_raw(
+        safe_calc(a.numer(), &T::one(), &Operator::Neg)?,
+        a.denom().clone(),
+    ))
+}
+
+// The denominator must be positive.
+pub fn reduced<T: CheckedType + CheckedCalc>(numer: T, denom: T) -> Result<Ratio<T>, ErrorCases> {
+    let gcd = numer.gcd(&denom);
+    Ok(Ratio::new_raw(
+        safe_calc(&numer, &gcd, &Operator::Div)?,
+        safe_calc(&denom, &gcd, &Operator::Div)?,
+    ))
 }
 
 // unit tests
 #[cfg(test)]
 mod tests {
     use super::AtomDict;
-    use std::collections::HashMap;
+    use crate::public::failures::ErrorCases;
+    use num::rational::Ratio;
 
     #[test]
     fn add_test() {
         let mut a = AtomDict::<i32>::new(); // CH4
         let mut b = AtomDict::<i32>::new(); // H2O
-        a.insert("C".to_string(), 1);
-        a.insert("H".to_string(), 4);
-        b.insert("H".to_string(), 2);
-        b.insert("O".to_string(), 1);
-        let c = a + b;
+        a.insert("C".to_string(), Ratio::from_integer(1));
+        a.insert("H".to_string(), Ratio::from_integer(4));
+        b.insert("H".to_string(), Ratio::from_integer(2));
+        b.insert("O".to_string(), Ratio::from_integer(1));
+        let c = a.checked_add(b).unwrap();
         assert_eq!(
             c.dict,
             [
-                ("H".to_string(), 6),
-                ("O".to_string(), 1),
-                ("C".to_string(), 1)
+                ("C".to_string(), Ratio::from_integer(1)),
+                ("H".to_string(), Ratio::from_integer(6)),
+                ("O".to_string(), Ratio::from_integer(1))
             ]
-            .iter()
-            .cloned()
-            .collect::<HashMap<String, i32>>()
         );
     }
 
     #[test]
     fn mul_test() {
-        let mut a = AtomDict::<i32>::new(); // CH4
-        a.insert("C".to_string(), 1);
-        a.insert("H".to_string(), 4);
-        let c = a * 2;
+        let mut a = AtomDict::<i32>::new(); // Fe0.95O
+        a.insert("Fe".to_string(), Ratio::new(19, 20));
+        a.insert("O".to_string(), Ratio::from_integer(1));
+        let c = a.checked_mul(&Ratio::from_integer(2)).unwrap();
         assert_eq!(
             c.get_dict(),
-            &[("H".to_string(), 8), ("C".to_string(), 2)]
-                .iter()
-                .cloned()
-                .collect::<HashMap<String, i32>>()
+            &[
+                ("Fe".to_string(), Ratio::new(19, 10)),
+                ("O".to_string(), Ratio::from_integer(2))
+            ]
+        );
+    }
+
+    #[test]
+    fn overflow_test() {
+        let mut a = AtomDict::<i32>::new();
+        a.insert("O".to_string(), Ratio::from_integer(i32::MAX));
+        assert_eq!(
+            a.checked_mul(&Ratio::from_integer(2)).unwrap_err(),
+            ErrorCases::Overflow
         );
     }
 }
@@ -15,10 +15,14 @@
 
 // Overall: This is the source code of the Delta-3 Parser.
 
-use std::collections::HashMap;
+use num::rational::Ratio;
 // inside use(s)
-use super::atomdict::AtomDict;
-use crate::public::traits::CheckedType;
+use super::atomdict::{add, neg, AtomDict};
+use crate::public::{
+    calc::{safe_calc, Operator},
+    failures::ErrorCases,
+    traits::{CheckedCalc, CheckedType},
+};
 
 // This is the data structure of describing the result of Delta-3 Parser.
 // This is the form of the `list`:
@@ -27,39 +31,77 @@ use crate::public::traits::CheckedType;
 // | element_2 | ...       | ...       | ...       | ...       |
 // | ...       | ...       | ...       | ...       | ...       |
 // | element_n | ...       | ...       | ...       | ...       |
-pub struct TableDesc<T: CheckedType> {
-    elements_table: HashMap<String, usize>, // store the index of elements
-    list: Vec<Vec<T>>,
+// The elements are sorted by their first appearance in the equation.
+// The entries may be fractional, each row is scaled to integers when the list is taken out.
+pub struct TableDesc<T: CheckedType + CheckedCalc> {
+    elements_table: Vec<String>, // the index of an element is its row
+    list: Vec<Vec<Ratio<T>>>,
     formula_sum: usize,
 }
 
-impl<T: CheckedType> TableDesc<T> {
-    pub fn store_in_table(&mut self, atomdict: &AtomDict<T>, location: usize, neg: bool) {
+impl<T: CheckedType + CheckedCalc> TableDesc<T> {
+    pub fn store_in_table(
+        &mut self,
+        atomdict: &AtomDict<T>,
+        location: usize,
+        negative: bool,
+    ) -> Result<(), ErrorCases> {
         for (k, v) in atomdict.get_dict().iter() {
-            if !self.elements_table.contains_key(k) {
-                let len = self.elements_table.len();
-                self.elements_table.insert(k.to_string(), len);
-                self.list.push(generate_vec(self.formula_sum));
-            }
+            let row = match self.elements_table.iter().position(|e| e == k) {
+                Some(s) => s,
+                None => {
+                    self.elements_table.push(k.to_string());
+                    self.list.push(generate_vec(self.formula_sum));
+                    self.elements_table.len() - 1
+                }
+            };
             // store data in table
-            let value = if neg { -(*v) } else { *v };
-            self.list[self.elements_table[k]][location] += value;
+            let value = if negative { neg(v)? } else { v.clone() };
+            self.list[row][location] = add(&self.list[row][location], &value)?;
         }
+        Ok(())
     }
 
-    pub fn get_list(&self) -> Vec<Vec<T>> {
-        (self.list).to_vec()
+    // Scaling a row doesn't change the solutions, since every row is an equation equal to zero.
+    pub fn get_list(&self) -> Result<Vec<Vec<T>>, ErrorCases> {
+        self.list
+            .iter()
+            .map(|row| {
+                // The denominators are positive.
+                let lcm = row.iter().try_fold(T::one(), |lcm, v| {
+                    safe_calc(
+                        &safe_calc(&lcm, &lcm.gcd(v.denom()), &Operator::Div)?,
+                        v.denom(),
+                        &Operator::Mul,
+                    )
+                })?;
+                row.iter()
+                    .map(|v| {
+                        safe_calc(
+                            v.numer(),
+                            &safe_calc(&lcm, v.denom(), &Operator::Div)?,
+                            &Operator::Mul,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()
+    }
+
+    // The name of the element of each row
+    pub fn get_elements(&self) -> Vec<String> {
+        self.elements_table.to_vec()
     }
 
     pub fn new(formula_sum: usize) -> Self {
         Self {
-            elements_table: HashMap::new(),
+            elements_table: Vec::new(),
             list: Vec::new(),
             formula_sum,
         }
     }
 }
 
-fn generate_vec<T: CheckedType>(capacity: usize) -> Vec<T> {
-    vec![T::zero(); capacity]
+fn generate_vec<T: CheckedType + CheckedCalc>(capacity: usize) -> Vec<Ratio<T>> {
+    vec![Ratio::from_integer(T::zero()); capacity]
 }
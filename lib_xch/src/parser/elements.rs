@@ -0,0 +1,192 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// Overall: This is the source code of the Delta-3 Parser.
+
+// The symbols of the elements, sorted by their atomic numbers.
+const SYMBOLS: [&str; 118] = [
+    "H", "He", "Li", "Be", "B", "C", "N", "O", "F", "Ne", "Na", "Mg", "Al", "Si", "P", "S", "Cl",
+    "Ar", "K", "Ca", "Sc", "Ti", "V", "Cr", "Mn", "Fe", "Co", "Ni", "Cu", "Zn", "Ga", "Ge", "As",
+    "Se", "Br", "Kr", "Rb", "Sr", "Y", "Zr", "Nb", "Mo", "Tc", "Ru", "Rh", "Pd", "Ag", "Cd", "In",
+    "Sn", "Sb", "Te", "I", "Xe", "Cs", "Ba", "La", "Ce", "Pr", "Nd", "Pm", "Sm", "Eu", "Gd", "Tb",
+    "Dy", "Ho", "Er", "Tm", "Yb", "Lu", "Hf", "Ta", "W", "Re", "Os", "Ir", "Pt", "Au", "Hg", "Tl",
+    "Pb", "Bi", "Po", "At", "Rn", "Fr", "Ra", "Ac", "Th", "Pa", "U", "Np", "Pu", "Am", "Cm", "Bk",
+    "Cf", "Es", "Fm", "Md", "No", "Lr", "Rf", "Db", "Sg", "Bh", "Hs", "Mt", "Ds", "Rg", "Cn", "Nh",
+    "Fl", "Mc", "Lv", "Ts", "Og",
+];
+
+// The standard atomic weights (abridged) in g/mol, in the same order as `SYMBOLS`.
+// The elements without stable isotopes take the mass number of their longest-lived isotope.
+const ATOMIC_WEIGHTS: [f64; 118] = [
+    1.008, 4.0026, 6.94, 9.0122, 10.81, 12.011, 14.007, 15.999, 18.998, 20.180, 22.990, 24.305,
+    26.982, 28.085, 30.974, 32.06, 35.45, 39.95, 39.098, 40.078, 44.956, 47.867, 50.942, 51.996,
+    54.938, 55.845, 58.933, 58.693, 63.546, 65.38, 69.723, 72.630, 74.922, 78.971, 79.904, 83.798,
+    85.468, 87.62, 88.906, 91.224, 92.906, 95.95, 98.0, 101.07, 102.91, 106.42, 107.87, 112.41,
+    114.82, 118.71, 121.76, 127.60, 126.90, 131.29, 132.91, 137.33, 138.91, 140.12, 140.91, 144.24,
+    145.0, 150.36, 151.96, 157.25, 158.93, 162.50, 164.93, 167.26, 168.93, 173.05, 174.97, 178.49,
+    180.95, 183.84, 186.21, 190.23, 192.22, 195.08, 196.97, 200.59, 204.38, 207.2, 208.98, 209.0,
+    210.0, 222.0, 223.0, 226.0, 227.0, 232.04, 231.04, 238.03, 237.0, 244.0, 243.0, 247.0, 247.0,
+    251.0, 252.0, 257.0, 258.0, 259.0, 262.0, 267.0, 268.0, 269.0, 270.0, 269.0, 278.0, 281.0,
+    282.0, 285.0, 286.0, 289.0, 290.0, 293.0, 294.0, 294.0,
+];
+
+// The masses in g/mol of the commonly labelled isotopes, as the mass number and the atomic number.
+const ISOTOPE_MASSES: [(usize, usize, f64); 20] = [
+    (1, 1, 1.007825),
+    (2, 1, 2.014102),
+    (3, 1, 3.016049),
+    (12, 6, 12.0),
+    (13, 6, 13.003355),
+    (14, 6, 14.003242),
+    (14, 7, 14.003074),
+    (15, 7, 15.000109),
+    (16, 8, 15.994915),
+    (17, 8, 16.999132),
+    (18, 8, 17.999160),
+    (31, 15, 30.973762),
+    (32, 15, 31.973908),
+    (32, 16, 31.972071),
+    (34, 16, 33.967867),
+    (35, 16, 34.969032),
+    (35, 17, 34.968853),
+    (37, 17, 36.965903),
+    (235, 92, 235.043930),
+    (238, 92, 238.050788),
+];
+
+// The atomic number of the element.
+pub fn atomic_number(symbol: &str) -> Option<usize> {
+    SYMBOLS.iter().position(|s| *s == symbol).map(|z| z + 1)
+}
+
+// The mass number and the atomic number of the nuclides which have their own symbols.
+pub fn special_nuclide(symbol: &str) -> Option<(usize, usize)> {
+    match symbol {
+        "D" => Some((2, 1)),
+        "T" => Some((3, 1)),
+        _ => None,
+    }
+}
+
+// `D` and `T` are written as `[2H]` and `[3H]`, so that they are the same as the isotopes.
+pub fn isotope_name(symbol: &str) -> Option<String> {
+    special_nuclide(symbol).map(|(mass, z)| format!("[{}{}]", mass, SYMBOLS[z - 1]))
+}
+
+// The molar mass of an atom, which may be an isotope written as `[13C]`, `D` or `T`.
+// The isotopes which aren't in `ISOTOPE_MASSES` take their mass numbers.
+pub fn atomic_weight(atom: &str) -> Option<f64> {
+    let (mass, z) = match special_nuclide(atom) {
+        Some(nuclide) => nuclide,
+        None if atom.starts_with('[') && atom.ends_with(']') => {
+            let inner = &atom[1..atom.len() - 1];
+            let split = inner.find(|c: char| !c.is_ascii_digit())?;
+            (
+                inner[..split].parse().ok()?,
+                atomic_number(&inner[split..])?,
+            )
+        }
+        None => return atomic_number(atom).map(|z| ATOMIC_WEIGHTS[z - 1]),
+    };
+    Some(
+        ISOTOPE_MASSES
+            .iter()
+            .find(|(a, b, _)| *a == mass && *b == z)
+            .map_or(mass as f64, |(_, _, m)| *m),
+    )
+}
+
+pub fn is_element(symbol: &str) -> bool {
+    atomic_number(symbol).is_some() || special_nuclide(symbol).is_some()
+}
+
+// The element whose symbol is the same ignoring the case, like `Cl` for `CL`.
+pub fn same_ignoring_case(symbol: &str) -> Option<&'static str> {
+    SYMBOLS
+        .iter()
+        .find(|s| s.eq_ignore_ascii_case(symbol))
+        .cloned()
+}
+
+// The element which is the most likely to be meant: the one with the least edit distance ignoring the case,
+// preferring the ones with the same first letter.
+pub fn closest_element(symbol: &str) -> &'static str {
+    let symbol = symbol.to_ascii_lowercase();
+    SYMBOLS
+        .iter()
+        .min_by_key(|s| {
+            let s = s.to_ascii_lowercase();
+            (
+                distance(&s, &symbol),
+                s.chars().next() != symbol.chars().next(),
+            )
+        })
+        .cloned()
+        .unwrap_or(SYMBOLS[0])
+}
+
+// The Levenshtein distance
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, x) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if x == *y {
+                prev
+            } else {
+                1 + prev.min(row[j]).min(row[j + 1])
+            };
+            prev = current;
+        }
+    }
+    row[b.len()]
+}
+
+// unit tests
+#[cfg(test)]
+mod tests {
+    use super::{atomic_number, atomic_weight, closest_element, isotope_name, same_ignoring_case};
+
+    #[test]
+    fn atomic_numbers() {
+        assert_eq!(atomic_number("H"), Some(1));
+        assert_eq!(atomic_number("U"), Some(92));
+        assert_eq!(atomic_number("Og"), Some(118));
+        assert_eq!(atomic_number("Xx"), None);
+    }
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(same_ignoring_case("CL"), Some("Cl"));
+        assert_eq!(same_ignoring_case("CLO"), None);
+        assert_eq!(closest_element("Xx"), "Xe");
+        assert_eq!(closest_element("L"), "Li");
+        assert_eq!(closest_element("Cll"), "Cl");
+    }
+
+    #[test]
+    fn weights() {
+        assert_eq!(atomic_weight("C"), Some(12.011));
+        assert_eq!(atomic_weight("[13C]"), Some(13.003355));
+        assert_eq!(atomic_weight("D"), Some(2.014102));
+        assert_eq!(atomic_weight("[60Co]"), Some(60.0));
+        assert_eq!(isotope_name("T"), Some("[3H]".to_string()));
+        assert_eq!(isotope_name("H"), None);
+        assert_eq!(atomic_weight("Xx"), None);
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+// Overall: This is the source code of the Delta-3 Parser.
+
+use pest::{
+    error::{Error, ErrorVariant, InputLocation},
+    iterators::Pair,
+    RuleType,
+};
+use std::ops::Range;
+// inside use(s)
+use crate::public::failures::{ErrorCases, ParseError};
+
+// The lowercase words which aren't element symbols
+const STATES: [&str; 4] = ["aq", "s", "l", "g"];
+const PARTICLES: [&str; 3] = ["n", "p", "e"];
+
+// The equation being parsed.
+// All the positions of the errors are counted in characters of the equation.
+#[derive(Clone, Copy)]
+pub struct Source<'a> {
+    input: &'a str,
+    nuclear: bool,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            nuclear: false,
+        }
+    }
+
+    // A nuclear reaction, whose lowercase particles are never element symbols
+    pub fn nuclear(input: &'a str) -> Self {
+        Self {
+            input,
+            nuclear: true,
+        }
+    }
+
+    // The position of the byte `index` of the equation
+    pub fn position(&self, index: usize) -> usize {
+        self.input[..index].chars().count()
+    }
+
+    pub fn span<R: RuleType>(&self, pair: &Pair<'_, R>) -> Range<usize> {
+        let span = pair.as_span();
+        self.position(span.start())..self.position(span.end())
+    }
+
+    // An empty span right after the pair, and what follows it
+    pub fn after<R: RuleType>(&self, pair: &Pair<'_, R>) -> (Range<usize>, Option<String>) {
+        let end = pair.as_span().end();
+        let position = self.position(end);
+        (
+            position..position,
+            self.input[end..].chars().next().map(|c| c.to_string()),
+        )
+    }
+
+    pub fn pest_error<R: RuleType>(&self, e: Error<R>) -> ErrorCases {
+        let (start, end) = match e.location {
+            InputLocation::Pos(p) => (p, p),
+            InputLocation::Span(s) => s,
+        };
+        let found = if start < end {
+            Some(self.input[start..end].to_string())
+        } else {
+            self.input[start..].chars().next().map(|c| c.to_string())
+        };
+        let end = start + found.as_ref().map_or(0, |s| s.len());
+        let (expected, hint) = match e.variant {
+            ErrorVariant::ParsingError { positives, .. } => {
+                let mut expected = Vec::<String>::new();
+                for name in positives
+                    .iter()
+                    .map(|rule| describe(&format!("{:?}", rule)))
+                {
+                    if !expected.contains(&name) {
+                        expected.push(name);
+                    }
+                }
+                let hint = if self.nuclear {
+                    None
+                } else {
+                    lowercase_hint(&self.input[..start], &self.input[start..])
+                };
+                (expected, hint)
+            }
+            ErrorVariant::CustomError { message } => (Vec::new(), Some(message)),
+        };
+        ErrorCases::ParserError(ParseError {
+            span: self.position(start)..self.position(end),
+            expected,
+            found,
+            hint,
+        })
+    }
+}
+
+pub fn error(
+    span: Range<usize>,
+    expected: &[&str],
+    found: Option<String>,
+    hint: Option<String>,
+) -> ErrorCases {
+    ErrorCases::ParserError(ParseError {
+        span,
+        expected: expected.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+        found,
+        hint,
+    })
+}
+
+// The errors which should never happen
+pub fn internal(message: &str) -> ErrorCases {
+    error(0..0, &[], None, Some(format!("[Internal] {}", message)))
+}
+
+// The name of a rule in the messages. Every rule of the grammar has one.
+fn describe(rule: &str) -> String {
+    match rule {
+        "WHITESPACE" => "a space",
+        "COMMENT" => "a comment",
+        "lalpha" => "a lowercase letter",
+        "ualpha" => "an uppercase letter",
+        "digit" | "sub_digit" => "a digit",
+        "num" => "a number",
+        "ratio" => "a fraction",
+        "atom" | "atom_name" => "an atom",
+        "isotope" => "an isotope",
+        "mass" => "a mass number",
+        "charge" | "electron" | "trailing_charge" => "a charge",
+        "sign" => "a sign",
+        "sup_num" => "a superscript number",
+        "sup_sign" => "a superscript sign",
+        "parenthesis_wrapper" => "a bracket",
+        "dot" => "a dot",
+        "molecule" | "molecule_group" | "formula" | "next_formula" | "exp" | "lone_formula" => {
+            "a chemical formula"
+        }
+        "particle" => "a particle",
+        "nuclide" | "nuclear_exp" => "a nuclide",
+        "state" => "a state",
+        "arrow" => "an arrow",
+        "equ" | "nuclear_equ" => "an equation",
+        "EOI" => "the end of the equation",
+        _ => rule,
+    }
+    .to_string()
+}
+
+// Element symbols are never lowercase, so suggest the capitalized and the uppercase ones.
+// The particles and the states (after `(`) are lowercase on purpose, so they get no hint.
+fn lowercase_hint(before: &str, rest: &str) -> Option<String> {
+    let word = rest
+        .chars()
+        .take_while(|c| c.is_ascii_lowercase())
+        .collect::<String>();
+    if PARTICLES.contains(&word.as_str())
+        || (before.ends_with('(') && STATES.contains(&word.as_str()))
+    {
+        return None;
+    }
+    let mut chars = word.chars();
+    let first = chars.next()?;
+    let capitalized = format!("{}{}", first.to_ascii_uppercase(), chars.as_str());
+    let uppercase = word.to_ascii_uppercase();
+    Some(if capitalized == uppercase {
+        format!(
+            "Lowercase element symbol '{}' — did you mean '{}'?",
+            word, uppercase
+        )
+    } else {
+        format!(
+            "Lowercase element symbol '{}' — did you mean '{}' or '{}'?",
+            word, capitalized, uppercase
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::describe;
+
+    #[test]
+    fn names() {
+        // Every rule of the grammar, so that a new one can't be left out
+        let rules = include_str!("../ast.pest")
+            .lines()
+            .filter_map(|line| line.split(" = ").next().filter(|_| line.contains(" = ")))
+            .chain(Some("EOI"))
+            .collect::<Vec<_>>();
+        assert!(rules.contains(&"sup_sign") && rules.contains(&"nuclear_equ"));
+        for rule in rules {
+            assert_ne!(describe(rule), rule);
+        }
+    }
+}
@@ -15,40 +15,444 @@
 
 // Overall: This is the source code of the Delta-3 Parser.
 
-use pest::Parser;
+use num::{rational::Ratio, Integer};
+use pest::{iterators::Pair, Parser};
 use pest_derive::Parser;
 // inside use(s)
-use super::{ast::treebuilder::ASTTreeBuilder, datastructure::TableDesc};
+use super::{
+    ast::treebuilder::{parse_from, ASTTreeBuilder},
+    atomdict::AtomDict,
+    datastructure::TableDesc,
+    elements::{atomic_number, closest_element, is_element, same_ignoring_case, special_nuclide},
+    error::{error, internal, Source},
+};
 use crate::public::{
     calc::{safe_calc, Operator},
     failures::ErrorCases,
-    structs::ChemicalEquation,
-    traits::CheckedType,
+    structs::{Arrow, ChemicalEquation, State},
+    traits::{CheckedCalc, CheckedType},
+    types::ParserOutput,
 };
 
 #[derive(Parser)]
 #[grammar = "ast.pest"]
-struct EquParser;
+pub struct EquParser;
+
+// Both the grammar and the AST are recursive, so the brackets nested too deeply would overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+// With `validate`, every element symbol must be in the periodic table.
+pub fn parser<T: CheckedType + CheckedCalc>(
+    equ: &str,
+    validate: bool,
+) -> Result<ParserOutput<T>, ErrorCases> {
+    equation_parser(equ, validate, false).map(|(output, _)| output)
+}
 
-pub fn parser<T: CheckedType>(equ: &str) -> Result<(ChemicalEquation, Vec<Vec<T>>), ErrorCases> {
-    let builder = ASTTreeBuilder::new();
-    let exps = EquParser::parse(Rule::equ, equ)
-        .map_err(|e| ErrorCases::ParserError(e.to_string()))?
+// Like `parser`, but the leading number of each chemical formula is read as its coefficient
+// instead of a part of it. The coefficients are returned along with the output.
+pub fn coefficient_parser<T: CheckedType + CheckedCalc>(
+    equ: &str,
+    validate: bool,
+) -> Result<(ParserOutput<T>, Vec<T>), ErrorCases> {
+    equation_parser(equ, validate, true)
+}
+
+fn equation_parser<T: CheckedType + CheckedCalc>(
+    equ: &str,
+    validate: bool,
+    as_coefficients: bool,
+) -> Result<(ParserOutput<T>, Vec<T>), ErrorCases> {
+    check_brackets(equ)?;
+    let source = Source::new(equ);
+    let builder = ASTTreeBuilder::new().isotopes(validate);
+    let pairs = EquParser::parse(Rule::equ, equ)
+        .map_err(|e| source.pest_error(e))?
         .collect::<Vec<_>>();
+    let (exps, arrows): (Vec<_>, Vec<_>) = pairs
+        .into_iter()
+        .filter(|pair| pair.as_rule() != Rule::EOI)
+        .partition(|pair| pair.as_rule() == Rule::exp);
+    if validate {
+        validate_symbols(&exps, &source)?;
+    }
+    check_dots(&exps, &source)?;
+    // The chemical formulas without the state annotations, and the states
+    let formulas = exps
+        .iter()
+        .flat_map(|exp| exp.clone().into_inner())
+        .map(|formula| {
+            let mut inner = formula.into_inner();
+            let group = inner.next().ok_or_else(|| internal("Empty formula"))?;
+            match inner.next() {
+                Some(state) => State::from_symbol(state.as_str())
+                    .map(|state| (group, Some(state)))
+                    .ok_or_else(|| internal("Unknown state")),
+                None => Ok((group, None)),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
     let mut ce_desc = ChemicalEquation {
         left: exps[0].clone().into_inner().count(),
         right: exps[1].clone().into_inner().count(),
         sum: 0,
+        species: formulas
+            .iter()
+            .map(|(group, _)| {
+                if as_coefficients {
+                    without_coefficient(group)
+                } else {
+                    group.as_str().trim().to_string()
+                }
+            })
+            .collect::<Vec<_>>(),
+        states: formulas.iter().map(|(_, state)| *state).collect::<Vec<_>>(),
+        arrow: parse_arrow(arrows[0].as_str())?,
     };
     ce_desc.sum = safe_calc(&ce_desc.left, &ce_desc.right, &Operator::Add)?;
     let mut table = TableDesc::new(ce_desc.sum);
-    for (location, formula) in exps[0].clone().into_inner().enumerate() {
-        let atomdict = builder.parse(formula.as_str())?.to_atomdict()?;
-        table.store_in_table(&atomdict, location, false);
+    let mut coefficients = Vec::<T>::new();
+    for (location, (group, _)) in formulas.into_iter().enumerate() {
+        let atomdict = if as_coefficients {
+            let (coefficient, tree) = builder.build_with_coefficient(group, &source)?;
+            coefficients.push(coefficient);
+            tree.to_atomdict()?
+        } else {
+            builder.build(group, &source)?.to_atomdict()?
+        };
+        table.store_in_table(&atomdict, location, location >= ce_desc.left)?;
     }
-    for (location, formula) in exps[1].clone().into_inner().enumerate() {
-        let atomdict = builder.parse(formula.as_str())?.to_atomdict()?;
-        table.store_in_table(&atomdict, location + ce_desc.left, true);
+    Ok((
+        (ce_desc, table.get_elements(), table.get_list()?),
+        coefficients,
+    ))
+}
+
+// The chemical formula without its leading number, which is its coefficient.
+fn without_coefficient(group: &Pair<'_, Rule>) -> String {
+    let s = group.as_str();
+    group
+        .clone()
+        .into_inner()
+        .next()
+        .and_then(|molecule| molecule.into_inner().next())
+        .filter(|p| p.as_rule() == Rule::num || p.as_rule() == Rule::ratio)
+        .map_or(s, |p| &s[p.as_span().end() - group.as_span().start()..])
+        .trim()
+        .to_string()
+}
+
+// Parse a single chemical formula, whose element symbols must be in the periodic table.
+// The atoms are in the order of their first appearance, with `"e"` for the electronic charge.
+pub fn formula_parser<T: CheckedType + CheckedCalc>(
+    formula: &str,
+) -> Result<Vec<(String, Ratio<T>)>, ErrorCases> {
+    check_brackets(formula)?;
+    let source = Source::new(formula);
+    let pairs = EquParser::parse(Rule::lone_formula, formula)
+        .map_err(|e| source.pest_error(e))?
+        .filter(|pair| pair.as_rule() == Rule::formula)
+        .collect::<Vec<_>>();
+    validate_symbols(&pairs, &source)?;
+    check_dots(&pairs, &source)?;
+    let group = pairs
+        .into_iter()
+        .next()
+        .and_then(|formula| formula.into_inner().next())
+        .ok_or_else(|| internal("Empty formula"))?;
+    Ok(ASTTreeBuilder::new()
+        .isotopes(true)
+        .build::<T>(group, &source)?
+        .to_atomdict()?
+        .get_dict()
+        .to_vec())
+}
+
+// Parse a nuclear reaction. The rows are the mass number `A` and the atomic number `Z`.
+pub fn nuclear_parser<T: CheckedType + CheckedCalc>(
+    equ: &str,
+) -> Result<ParserOutput<T>, ErrorCases> {
+    check_brackets(equ)?;
+    let source = Source::nuclear(equ);
+    let pairs = EquParser::parse(Rule::nuclear_equ, equ)
+        .map_err(|e| source.pest_error(e))?
+        .collect::<Vec<_>>();
+    let (exps, arrows): (Vec<_>, Vec<_>) = pairs
+        .into_iter()
+        .filter(|pair| pair.as_rule() != Rule::EOI)
+        .partition(|pair| pair.as_rule() == Rule::nuclear_exp);
+    let nuclides = exps
+        .iter()
+        .flat_map(|exp| exp.clone().into_inner())
+        .collect::<Vec<_>>();
+    let mut ce_desc = ChemicalEquation {
+        left: exps[0].clone().into_inner().count(),
+        right: exps[1].clone().into_inner().count(),
+        sum: 0,
+        species: nuclides
+            .iter()
+            .map(|nuclide| nuclide.as_str().trim().to_string())
+            .collect::<Vec<_>>(),
+        states: vec![None; nuclides.len()],
+        arrow: parse_arrow(arrows[0].as_str())?,
+    };
+    ce_desc.sum = safe_calc(&ce_desc.left, &ce_desc.right, &Operator::Add)?;
+    let mut table = TableDesc::new(ce_desc.sum);
+    for (location, nuclide) in nuclides.into_iter().enumerate() {
+        table.store_in_table(
+            &to_nucleons(nuclide, &source)?,
+            location,
+            location >= ce_desc.left,
+        )?;
     }
-    Ok((ce_desc, table.get_list()))
+    Ok((ce_desc, table.get_elements(), table.get_list()?))
+}
+
+// Reject the first symbol which isn't an element.
+// `CL` is read as `C` and `L`, so the symbol is joined with its neighbours to find out what is meant.
+fn validate_symbols(exps: &[Pair<'_, Rule>], source: &Source<'_>) -> Result<(), ErrorCases> {
+    let names = exps
+        .iter()
+        .flat_map(|exp| exp.clone().into_inner().flatten())
+        .filter(|pair| pair.as_rule() == Rule::atom_name)
+        .collect::<Vec<_>>();
+    let adjacent =
+        |a: &Pair<'_, Rule>, b: &Pair<'_, Rule>| a.as_span().end() == b.as_span().start();
+    for (i, name) in names.iter().enumerate() {
+        if is_element(name.as_str()) {
+            continue;
+        }
+        let joined = [
+            i.checked_sub(1).map(|j| (&names[j], name)),
+            names.get(i + 1).map(|next| (name, next)),
+        ]
+        .iter()
+        .flatten()
+        .filter(|(a, b)| adjacent(a, b))
+        .find_map(|(a, b)| {
+            let word = format!("{}{}", a.as_str(), b.as_str());
+            same_ignoring_case(&word)
+                .map(|element| (source.span(a).start..source.span(b).end, word, element))
+        });
+        let (span, found, element) = joined.unwrap_or_else(|| {
+            (
+                source.span(name),
+                name.as_str().to_string(),
+                closest_element(name.as_str()),
+            )
+        });
+        return Err(error(
+            span,
+            &["an element symbol"],
+            Some(found.clone()),
+            Some(format!(
+                "Unknown element '{}' — did you mean '{}'?",
+                found, element
+            )),
+        ));
+    }
+    Ok(())
+}
+
+// A `.` between two numbers is a hydrate dot, like `CuSO4.5H2O` or `CaCl2.8NH3`, but `Fe1.5O` is rather a decimal,
+// as nobody writes the subscript `1` before a hydrate dot. Such a `.` is rejected, so that it is never misread.
+fn check_dots(pairs: &[Pair<'_, Rule>], source: &Source<'_>) -> Result<(), ErrorCases> {
+    let groups = pairs
+        .iter()
+        .flat_map(|pair| pair.clone().into_inner().flatten())
+        .filter(|pair| pair.as_rule() == Rule::molecule_group);
+    for group in groups {
+        let text = group.as_str();
+        let offset = group.as_span().start();
+        let molecules = group.into_inner().collect::<Vec<_>>();
+        for (prev, next) in molecules.iter().zip(molecules.iter().skip(1)) {
+            let prefix = match next.clone().into_inner().next() {
+                Some(prefix) if prefix.as_rule() == Rule::num => prefix,
+                _ => continue,
+            };
+            let (end, start) = (prev.as_span().end(), next.as_span().start());
+            // The subscript `1` right before the `.`
+            let one = prev.clone().into_inner().flatten().any(|pair| {
+                pair.as_rule() == Rule::num && pair.as_span().end() == end && pair.as_str() == "1"
+            });
+            if !one
+                || &text[end - offset..start - offset] != "."
+                || !prefix.as_str().chars().all(|c| c.is_ascii_digit())
+            {
+                continue;
+            }
+            let found = &text[end - offset - 1..prefix.as_span().end() - offset];
+            return Err(error(
+                source.position(end - 1)..source.position(prefix.as_span().end()),
+                &[],
+                Some(found.to_string()),
+                Some(format!(
+                    "'{}' may be a decimal or a hydrate — write the subscript as a fraction like '{}', or use '·' for a hydrate",
+                    found,
+                    fraction(found).unwrap_or_else(|| "3/2".to_string())
+                )),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// The decimal as a reduced fraction, like `3/2` for `1.5`
+fn fraction(decimal: &str) -> Option<String> {
+    let point = decimal.find('.')?;
+    let numer = decimal.replacen('.', "", 1).parse::<u64>().ok()?;
+    let denom = (point + 1..decimal.len()).try_fold(1u64, |d, _| d.checked_mul(10))?;
+    let gcd = numer.gcd(&denom);
+    Some(format!("{}/{}", numer / gcd, denom / gcd))
+}
+
+// The mass number and the atomic number of the nuclide, multiplied by its prefix.
+fn to_nucleons<T: CheckedType + CheckedCalc>(
+    nuclide: Pair<'_, Rule>,
+    source: &Source<'_>,
+) -> Result<AtomDict<T>, ErrorCases> {
+    let mut pairs = nuclide.into_inner().peekable();
+    let prefix = match pairs.next_if(|pair| pair.as_rule() == Rule::num) {
+        Some(pair) => parse_number(&pair)?,
+        None => T::one(),
+    };
+    let pairs = pairs.collect::<Vec<_>>();
+    let (mass, z) = match pairs[0].as_rule() {
+        Rule::particle => match pairs[0].as_str() {
+            "n" => (T::one(), T::zero()),
+            "p" => (T::one(), T::one()),
+            _ => (T::zero(), -T::one()), // The electron
+        },
+        Rule::isotope => {
+            let inner_pairs = pairs[0].clone().into_inner().collect::<Vec<_>>();
+            (
+                parse_number(&inner_pairs[0])?,
+                parse_atomic_number(&inner_pairs[1], source)?,
+            )
+        }
+        _ => {
+            let symbol = pairs[0].as_str();
+            match (pairs.get(1), special_nuclide(symbol)) {
+                (Some(mass), _) => (parse_number(mass)?, parse_atomic_number(&pairs[0], source)?),
+                (None, Some((mass, z))) => (
+                    parse_from(&mass.to_string()).ok_or(ErrorCases::Overflow)?,
+                    parse_from(&z.to_string()).ok_or(ErrorCases::Overflow)?,
+                ),
+                (None, None) => {
+                    let (span, found) = source.after(&pairs[0]);
+                    return Err(error(
+                        span,
+                        &["a mass number"],
+                        found,
+                        Some(format!("The mass number of '{}' is missing", symbol)),
+                    ));
+                }
+            }
+        }
+    };
+    let mut nucleons = AtomDict::<T>::new();
+    nucleons.insert("A".to_string(), Ratio::from_integer(mass));
+    nucleons.insert("Z".to_string(), Ratio::from_integer(z));
+    nucleons.checked_mul(&Ratio::from_integer(prefix))
+}
+
+fn parse_number<T: CheckedType>(pair: &Pair<'_, Rule>) -> Result<T, ErrorCases> {
+    parse_from(pair.as_str()).ok_or(ErrorCases::Overflow)
+}
+
+fn parse_atomic_number<T: CheckedType>(
+    pair: &Pair<'_, Rule>,
+    source: &Source<'_>,
+) -> Result<T, ErrorCases> {
+    let symbol = pair.as_str();
+    let span = source.span(pair);
+    let z = atomic_number(symbol)
+        .or_else(|| special_nuclide(symbol).map(|(_, z)| z))
+        .ok_or_else(|| {
+            error(
+                span,
+                &["an element symbol"],
+                Some(symbol.to_string()),
+                Some(format!("Unknown element '{}'", symbol)),
+            )
+        })?;
+    parse_from(&z.to_string()).ok_or(ErrorCases::Overflow)
+}
+
+// Check that every bracket is closed by the same kind of bracket, so that it can be reported clearly.
+// The brackets inside the comments are skipped.
+fn check_brackets(equ: &str) -> Result<(), ErrorCases> {
+    let mut stack = Vec::<(char, usize)>::new();
+    let mut chars = equ.chars().enumerate().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '/' if chars.peek().map(|(_, c)| *c) == Some('*') => {
+                // Skip the comment
+                chars.next();
+                let mut last = ' ';
+                for (_, c) in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+            }
+            '(' | '[' | '{' if stack.len() == MAX_DEPTH => {
+                return Err(error(
+                    i..i + 1,
+                    &[],
+                    Some(c.to_string()),
+                    Some(format!(
+                        "Brackets can't be nested deeper than {}",
+                        MAX_DEPTH
+                    )),
+                ))
+            }
+            '(' | '[' | '{' => stack.push((c, i)),
+            ')' | ']' | '}' => match stack.pop() {
+                Some((open, _)) if closing(open) == c => (),
+                Some((open, position)) => {
+                    return Err(error(
+                        i..i + 1,
+                        &[&format!("'{}'", closing(open))],
+                        Some(c.to_string()),
+                        Some(format!("'{}' is opened at {}", open, position + 1)),
+                    ))
+                }
+                None => {
+                    return Err(error(
+                        i..i + 1,
+                        &[],
+                        Some(c.to_string()),
+                        Some("No bracket is opened before it".to_string()),
+                    ))
+                }
+            },
+            _ => (),
+        }
+    }
+    match stack.pop() {
+        Some((open, position)) => {
+            let end = equ.chars().count();
+            Err(error(
+                end..end,
+                &[&format!("'{}'", closing(open))],
+                None,
+                Some(format!("'{}' is opened at {}", open, position + 1)),
+            ))
+        }
+        None => Ok(()),
+    }
+}
+
+fn closing(open: char) -> char {
+    match open {
+        '(' => ')',
+        '[' => ']',
+        _ => '}',
+    }
+}
+
+fn parse_arrow(s: &str) -> Result<Arrow, ErrorCases> {
+    Arrow::from_symbol(s).ok_or_else(|| internal("Unknown arrow"))
 }
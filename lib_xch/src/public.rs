@@ -19,6 +19,8 @@ pub(crate) mod calc;
 pub(crate) mod cell;
 pub mod failures;
 pub mod handler;
+pub mod mass;
+pub mod solvers;
 pub mod structs;
 pub mod traits;
 pub(crate) mod types;
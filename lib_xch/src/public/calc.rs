@@ -14,9 +14,7 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::public::failures::ErrorCases;
-use num::traits::ops::checked::{
-    CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedRem, CheckedSub,
-};
+use num::traits::ops::checked::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
 // inside use(s)
 use super::{failures::ErrorCases::Overflow, traits::CheckedCalc};
 
@@ -36,7 +34,11 @@ pub fn safe_calc<T: CheckedCalc>(a: &T, b: &T, op: &Operator) -> Result<T, Error
         Operator::Sub => CheckedSub::checked_sub(a, b).ok_or(Overflow),
         Operator::Mul => CheckedMul::checked_mul(a, b).ok_or(Overflow),
         Operator::Div => CheckedDiv::checked_div(a, b).ok_or(Overflow),
-        Operator::Rem => CheckedRem::checked_rem(a, b).ok_or(Overflow),
-        Operator::Neg => CheckedNeg::checked_neg(a).ok_or(Overflow),
+        // `a % b = a - a / b * b`
+        Operator::Rem => CheckedDiv::checked_div(a, b)
+            .and_then(|q| CheckedMul::checked_mul(&q, b))
+            .and_then(|p| CheckedSub::checked_sub(a, &p))
+            .ok_or(Overflow),
+        Operator::Neg => CheckedSub::checked_sub(&T::zero(), a).ok_or(Overflow),
     }
 }
@@ -17,6 +17,7 @@
 
 // mods
 mod base;
+mod checked;
 mod fromstr;
 mod integer;
 mod num;
@@ -33,7 +34,6 @@ pub struct Cell<U> {
 }
 
 impl<U> Cell<U> {
-    #[cfg(test)]
     pub fn new(data: U) -> Self {
         Cell {
             error_tag: false,
@@ -48,11 +48,8 @@ impl<U> Cell<U> {
     }
 }
 
-impl<U: CheckedType + CheckedCalc> CheckedType for Cell<U> where
-    std::num::ParseIntError: std::convert::From<<U as ::num::Num>::FromStrRadixErr>
-        + std::convert::From<<U as std::str::FromStr>::Err>
-{
-}
+impl<U: CheckedType + CheckedCalc> CheckedType for Cell<U> {}
+impl<U: CheckedType + CheckedCalc> CheckedCalc for Cell<U> {}
 
 // impls of `Eq`, `PartialEq`, `Ord`, `PartialOrd`
 impl<U: PartialEq> PartialEq for Cell<U> {
@@ -71,7 +68,7 @@ impl<U: PartialOrd + Ord> Ord for Cell<U> {
 
 impl<U: PartialEq + Ord> PartialOrd for Cell<U> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(&other))
+        Some(self.cmp(other))
     }
 }
 
@@ -79,40 +76,51 @@ impl<U: PartialEq + Ord> PartialOrd for Cell<U> {
 #[cfg(test)]
 mod tests {
     use super::Cell;
+    use num::traits::ops::checked::{CheckedAdd, CheckedMul};
 
     #[test]
     fn overflow_test_1() {
-        let a = Cell::<i32>::new(std::i32::MAX);
+        let a = Cell::<i32>::new(i32::MAX);
         let b = Cell::<i32>::new(1);
         let c = a + b;
-        assert_eq!(c.error_tag, true);
+        assert!(c.error_tag);
         assert_eq!(c, Cell::<i32>::new(1));
     }
 
     #[test]
     fn overflow_test_2() {
-        let a = Cell::<i32>::new(std::i32::MIN);
+        let a = Cell::<i32>::new(i32::MIN);
         let b = Cell::<i32>::new(1);
         let c = a - b;
-        assert_eq!(c.error_tag, true);
+        assert!(c.error_tag);
         assert_eq!(c, Cell::<i32>::new(1));
     }
 
     #[test]
     fn overflow_test_3() {
-        let a = Cell::<i32>::new(std::i32::MAX);
+        let a = Cell::<i32>::new(i32::MAX);
         let b = Cell::<i32>::new(2);
         let c = a * b;
-        assert_eq!(c.error_tag, true);
+        assert!(c.error_tag);
         assert_eq!(c, Cell::<i32>::new(1));
     }
 
     #[test]
     fn overflow_test_4() {
-        let a = Cell::<i32>::new(std::i32::MIN);
+        let a = Cell::<i32>::new(i32::MIN);
         let b = Cell::<i32>::new(-1);
         let c = a / b;
-        assert_eq!(c.error_tag, true);
+        assert!(c.error_tag);
         assert_eq!(c, Cell::<i32>::new(1));
     }
+
+    #[test]
+    fn checked_test() {
+        let a = Cell::<i32>::new(i32::MAX);
+        let b = Cell::<i32>::new(2);
+        assert_eq!(a.checked_mul(&b), None);
+        // The overflowed `Cell` can't be used any more.
+        assert_eq!((a * b).checked_add(&b), None);
+        assert_eq!(b.checked_add(&b), Some(Cell::<i32>::new(4)));
+    }
 }
@@ -13,6 +13,9 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
+// The error tags are merged by `|` on purpose
+#![allow(clippy::suspicious_op_assign_impl)]
+
 use num::{One, Zero};
 use std::{
     fmt::{Display, Error, Formatter},
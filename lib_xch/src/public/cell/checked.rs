@@ -0,0 +1,56 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+use num::{
+    traits::ops::checked::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub},
+    One,
+};
+// inside use(s)
+use super::{
+    super::{
+        calc::{safe_calc, Operator},
+        traits::CheckedCalc,
+    },
+    Cell,
+};
+
+// A tagged `Cell` has overflowed before, so the checked operations on it fail as well.
+fn checked<U: CheckedCalc>(a: &Cell<U>, b: &Cell<U>, op: &Operator) -> Option<Cell<U>> {
+    if a.error_tag || b.error_tag {
+        return None;
+    }
+    safe_calc(&a.data, &b.data, op).ok().map(Cell::new)
+}
+
+impl<U: One + CheckedCalc> CheckedAdd for Cell<U> {
+    fn checked_add(&self, v: &Self) -> Option<Self> {
+        checked(self, v, &Operator::Add)
+    }
+}
+impl<U: One + CheckedCalc> CheckedSub for Cell<U> {
+    fn checked_sub(&self, v: &Self) -> Option<Self> {
+        checked(self, v, &Operator::Sub)
+    }
+}
+impl<U: One + CheckedCalc> CheckedMul for Cell<U> {
+    fn checked_mul(&self, v: &Self) -> Option<Self> {
+        checked(self, v, &Operator::Mul)
+    }
+}
+impl<U: One + CheckedCalc> CheckedDiv for Cell<U> {
+    fn checked_div(&self, v: &Self) -> Option<Self> {
+        checked(self, v, &Operator::Div)
+    }
+}
@@ -13,15 +13,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{num::ParseIntError, str::FromStr};
+use std::str::FromStr;
 // inside use(s)
 use super::Cell;
 
-impl<U: FromStr> FromStr for Cell<U>
-where
-    std::num::ParseIntError: std::convert::From<<U as std::str::FromStr>::Err>,
-{
-    type Err = ParseIntError;
+impl<U: FromStr> FromStr for Cell<U> {
+    type Err = U::Err;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let data = U::from_str(s)?;
         Ok(Cell {
@@ -20,10 +20,7 @@ use super::{
     Cell,
 };
 
-impl<U: CheckedType + CheckedCalc> Integer for Cell<U>
-where
-    std::num::ParseIntError: std::convert::From<<U as num::Num>::FromStrRadixErr>,
-{
+impl<U: CheckedType + CheckedCalc> Integer for Cell<U> {
     fn div_floor(&self, other: &Self) -> Self {
         Cell {
             error_tag: self.error_tag | other.error_tag,
@@ -49,7 +46,7 @@ where
         }
     }
     fn divides(&self, other: &Self) -> bool {
-        self.data.divides(&other.data)
+        self.data.is_multiple_of(&other.data)
     }
     fn is_multiple_of(&self, other: &Self) -> bool {
         self.data.is_multiple_of(&other.data)
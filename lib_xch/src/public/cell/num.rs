@@ -14,15 +14,11 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use num::Num;
-use std::num::ParseIntError;
 // inside use(s)
 use super::{super::traits::CheckedCalc, Cell};
 
-impl<U: Num + CheckedCalc> Num for Cell<U>
-where
-    std::num::ParseIntError: std::convert::From<<U as num::Num>::FromStrRadixErr>,
-{
-    type FromStrRadixErr = ParseIntError;
+impl<U: Num + CheckedCalc> Num for Cell<U> {
+    type FromStrRadixErr = U::FromStrRadixErr;
     fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
         let data = U::from_str_radix(str, radix)?;
         Ok(Cell {
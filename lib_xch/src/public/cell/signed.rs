@@ -17,10 +17,7 @@ use num::Signed;
 // inside use(s)
 use super::{super::traits::CheckedCalc, Cell};
 
-impl<U: Signed + CheckedCalc> Signed for Cell<U>
-where
-    std::num::ParseIntError: std::convert::From<<U as num::Num>::FromStrRadixErr>,
-{
+impl<U: Signed + CheckedCalc> Signed for Cell<U> {
     fn abs(&self) -> Self {
         Cell {
             error_tag: self.error_tag,
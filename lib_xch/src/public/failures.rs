@@ -15,7 +15,14 @@
 
 //! Failures which exposes to the public
 
+// `failure_derive` generates its impls inside an anonymous `const`
+#![allow(non_local_definitions)]
+
 use failure::Fail;
+use std::{
+    fmt::{Display, Error, Formatter},
+    ops::Range,
+};
 
 /// All the Error Types.
 #[derive(PartialEq, Fail, Debug)]
@@ -23,10 +30,56 @@ pub enum ErrorCases {
     /// Overflow.
     #[fail(display = "Overflow occured during calculation")]
     Overflow,
-    /// Parser's error with a message.
+    /// Parser's error, with where and why it happened.
     #[fail(display = "{}", _0)]
-    ParserError(String),
+    ParserError(ParseError),
     /// Only the [zero solution](http://www.mathwords.com/t/trivial.htm) can be found.
+    ///
+    /// `Handler::diagnose` explains which elements and chemical formulas cause it.
     #[fail(display = "AlphaForce can only find trivial solution")]
     ZeroSolution,
+    /// There is no solution whose coefficients are all positive.
+    #[fail(display = "AlphaForce can't find any solution whose coefficients are all positive")]
+    NoPositiveSolution,
+    /// The search has been stopped because there are too many candidates to check.
+    #[fail(display = "AlphaForce gave up the search after checking too many candidates")]
+    SearchLimit,
+    /// The number of the coefficients isn't the number of the chemical formulas, which are the first and the second field.
+    #[fail(display = "Expected {} coefficients, found {}", _0, _1)]
+    CoefficientCount(usize, usize),
+}
+
+/// The details of a parser's error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// Where the error is in the equation, counted in characters from zero.
+    pub span: Range<usize>,
+    /// What could have been there instead, like `"an atom"` or `"')'"`.
+    pub expected: Vec<String>,
+    /// What is there. It is `None` at the end of the equation.
+    pub found: Option<String>,
+    /// A suggestion or an explanation.
+    pub hint: Option<String>,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let position = self.span.start + 1;
+        match (&self.found, self.expected.is_empty()) {
+            (Some(found), _) => write!(f, "Unexpected '{}' at {}", found, position)?,
+            (None, false) => write!(f, "Unexpected end of the equation at {}", position)?,
+            (None, true) => write!(f, "Error at {}", position)?,
+        }
+        if let Some((last, rest)) = self.expected.split_last() {
+            if rest.is_empty() {
+                write!(f, ", expected {}", last)?;
+            } else {
+                write!(f, ", expected {} or {}", rest.join(", "), last)?;
+            }
+        }
+        if let Some(hint) = &self.hint {
+            write!(f, ". {}", hint)?;
+        }
+        Ok(())
+    }
 }
@@ -25,13 +25,24 @@ use std::collections::HashMap;
 // inside use(s)
 use super::{
     failures::ErrorCases,
+    solvers::{GaussJordan, NullSpaceSolver},
     traits::{CheckedCalc, CheckedType},
-    types::DataSet,
+    types::{DataSet, ParserOutput},
 };
 use crate::{
-    balancer::handler::balancer,
-    parser::handler::parser,
-    public::{cell::Cell, structs::ChemicalEquation},
+    balancer::handler::{
+        balancer, diagnose, elementary_reactions, minimal_positive, totals, warnings,
+    },
+    parser::handler::{coefficient_parser, nuclear_parser, parser},
+    public::{
+        calc::{safe_calc, Operator},
+        cell::Cell,
+        mass::molar_mass,
+        structs::{
+            AutoBalanceResult, BalanceResult, ChemicalEquation, Diagnosis, Residual, Verification,
+            Warning,
+        },
+    },
 };
 
 /// A handler which store the equation and other information
@@ -39,24 +50,57 @@ pub struct Handler<'a, T> {
     equ: &'a str,
     ds: HashMap<&'static str, Vec<Vec<Cell<T>>>>,
     cd: ChemicalEquation,
+    basis: Vec<Vec<T>>,
+    elements: Vec<String>,
+    solver: Box<dyn NullSpaceSolver<T> + 'a>,
+    nuclear: bool,
+    validate: bool,
 }
 
-impl<'a, T: CheckedType + CheckedCalc> Handler<'a, T>
-where
-    std::num::ParseIntError: std::convert::From<<T as num::Num>::FromStrRadixErr>
-        + std::convert::From<<T as std::str::FromStr>::Err>,
-{
+impl<'a, T: CheckedType + CheckedCalc> Handler<'a, T> {
     /// Create a `Handler` by given equation
     pub fn new(equ: &'a str) -> Self {
         Handler {
             equ,
             ds: HashMap::new(),
             cd: ChemicalEquation::new(),
+            basis: Vec::new(),
+            elements: Vec::new(),
+            solver: Box::new(GaussJordan),
+            nuclear: false,
+            validate: false,
         }
     }
+
+    /// Choose the solver which computes the Basic Solutions. The default one is `solvers::GaussJordan`.
+    ///
+    /// The built-in solvers give the same Basic Solutions, but they differ in speed and in how soon they overflow.
+    pub fn solver<S: NullSpaceSolver<T> + 'a>(mut self, solver: S) -> Self {
+        self.solver = Box::new(solver);
+        self
+    }
+
+    /// Balance a nuclear reaction, such as `U235+n=Ba141+Kr92+3n`. The default is `false`.
+    ///
+    /// In the nuclear mode, every chemical formula is a nuclide (`U235`, `[235U]` or `^235U`, the mass number is required except for `D` and `T`)
+    /// or a particle (`n`, `p` or `e`), and the rows are the mass number `"A"` and the atomic number `"Z"` instead of the elements.
+    pub fn nuclear(mut self, nuclear: bool) -> Self {
+        self.nuclear = nuclear;
+        self
+    }
+
+    /// Check every element symbol against the periodic table. The default is `false`, so that any symbol like `A` can be used.
+    ///
+    /// An unknown symbol is rejected with the closest element as a hint, e.g. `CL` (which would be read as `C` and `L`) suggests `Cl`.
+    /// `D` and `T` are accepted as the isotopes of hydrogen and read as `[2H]` and `[3H]`, otherwise they are kept as written. It has no effect in the nuclear mode, which always checks the symbols.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
     /// Parse and balance the equation. If it has been parsed, then just balance it.
     ///
-    /// If the equation can balance, function would return `Ok((ChemicalEquation, Vec<Vec<T>>))` which contains the answer.
+    /// If the equation can balance, function would return `Ok(BalanceResult<T>)` which contains the answer.
     ///
     /// If not, it would return `Err(ErrorCases)` which contains the failure.
     ///
@@ -65,32 +109,361 @@ where
     /// -  A large number (bigger than [`usize::MAX`](https://doc.rust-lang.org/std/usize/constant.MAX.html)) of formula may cause **panic**. Because it is using `Vec`.
     ///
     /// And in the other failed situation, it'll return  `ErrorCases`.
-    pub fn handle(&mut self) -> Result<DataSet<&T>, ErrorCases> {
+    pub fn handle(&mut self) -> Result<BalanceResult<T>, ErrorCases> {
+        self.parse()?;
+        self.balance()?;
+        let matrix = owned(fromcell(&self.ds["Parser"])?);
+        // Every Basic Solution must conserve every row.
+        debug_assert!(self.basis.iter().all(|x| {
+            totals((&self.cd, &matrix), x).map_or(true, |t| t.iter().all(|(l, r)| l == r))
+        }));
+        let solutions = self
+            .basis
+            .iter()
+            .map(|v| {
+                self.cd
+                    .species
+                    .iter()
+                    .cloned()
+                    .zip(v.iter().cloned())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let (zeros, wrong_side) = warnings(&self.basis);
+        let warnings = zeros
+            .into_iter()
+            .map(|j| Warning::AlwaysZero(j, self.cd.species[j].clone()))
+            .chain(
+                wrong_side
+                    .into_iter()
+                    .map(|j| Warning::WrongSide(j, self.cd.species[j].clone())),
+            )
+            .collect::<Vec<_>>();
+        Ok(BalanceResult {
+            equation: self.cd.clone(),
+            elements: self.elements.clone(),
+            matrix,
+            solutions,
+            warnings,
+        })
+    }
+
+    /// Parse and balance the equation, then find the solution whose coefficients are all positive integers and whose sum is the smallest.
+    ///
+    /// It returns `Err(ErrorCases::NoPositiveSolution)` if there is no such a solution,
+    /// and `Err(ErrorCases::SearchLimit)` if there are too many candidates to check.
+    pub fn minimal_positive(&mut self) -> Result<Vec<(String, T)>, ErrorCases> {
         self.parse()?;
         self.balance()?;
-        Ok((&self.cd, fromcell(&self.ds["Balancer"])?))
+        Ok(self
+            .cd
+            .species
+            .iter()
+            .cloned()
+            .zip(minimal_positive(&self.basis)?)
+            .collect::<Vec<_>>())
+    }
+
+    /// Parse and balance the equation, then enumerate the elementary reactions.
+    ///
+    /// The elementary reactions are the extreme rays of the cone of the solutions whose coefficients are all non-negative.
+    /// Every non-negative solution is a non-negative combination of them, and none of them is a combination of the others.
+    /// It returns an empty `Vec` if there is no non-negative solution other than zero.
+    pub fn elementary_reactions(&mut self) -> Result<Vec<Vec<(String, T)>>, ErrorCases> {
+        self.parse()?;
+        self.balance()?;
+        Ok(elementary_reactions(&self.basis)?
+            .into_iter()
+            .map(|v| self.cd.species.iter().cloned().zip(v).collect::<Vec<_>>())
+            .collect::<Vec<_>>())
+    }
+
+    /// Parse the equation, then explain why it can't be balanced.
+    ///
+    /// It finds the elements which only appear on one side, the chemical formulas whose coefficients are zero in every solution,
+    /// and a minimal subset of the elements which forces them to zero.
+    /// All of them are empty if every chemical formula can take part in the reaction.
+    pub fn diagnose(&mut self) -> Result<Diagnosis, ErrorCases> {
+        self.parse()?;
+        let matrix = owned(fromcell(&self.ds["Parser"])?);
+        let (one_sided, zeros, rows) = diagnose((&self.cd, &matrix), self.solver.as_ref())?;
+        Ok(Diagnosis {
+            one_sided_elements: one_sided
+                .into_iter()
+                .map(|i| self.elements[i].clone())
+                .collect::<Vec<_>>(),
+            zero_species: zeros
+                .into_iter()
+                .map(|j| self.cd.species[j].clone())
+                .collect::<Vec<_>>(),
+            inconsistent_elements: rows
+                .into_iter()
+                .map(|i| self.elements[i].clone())
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Parse the equation, then check the coefficients against it.
+    ///
+    /// There is one coefficient for each chemical formula from left to right, all positive for an ordinary reaction,
+    /// like a solution of `handle`. It reports the totals of every element on both sides, and the molar masses of them.
+    ///
+    /// It returns `Err(ErrorCases::CoefficientCount)` if the number of the coefficients is wrong.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lib_xch::public::handler::Handler;
+    ///
+    /// let v = Handler::<i32>::new("H2+O2=H2O").verify(&[2, 1, 2]).unwrap();
+    /// assert!(v.is_balanced());
+    /// ```
+    pub fn verify(&mut self, coefficients: &[T]) -> Result<Verification<T>, ErrorCases> {
+        self.parse()?;
+        self.report(coefficients)
+    }
+
+    /// Read the leading number of each chemical formula as its coefficient, then check them like `verify`.
+    ///
+    /// So `2H2+O2=2H2O` is balanced, and a chemical formula without a leading number has one as its coefficient.
+    /// Only the number before the whole chemical formula is a coefficient, e.g. `2CuSO4.5H2O` is two of `CuSO4.5H2O`.
+    /// The coefficients must be integers.
+    ///
+    /// In the nuclear mode, the leading numbers are always a part of the nuclides like `3n`, which gives the same totals.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lib_xch::public::handler::Handler;
+    ///
+    /// let v = Handler::<i32>::new("2H2+O2=2H2O").check().unwrap();
+    /// assert!(v.is_balanced());
+    /// assert_eq!(v.coefficients[0], ("H2".to_string(), 2));
+    /// ```
+    pub fn check(&mut self) -> Result<Verification<T>, ErrorCases> {
+        let coefficients = if self.nuclear {
+            self.parse()?;
+            vec![T::one(); self.cd.sum]
+        } else {
+            let (output, coefficients) = coefficient_parser::<Cell<T>>(self.equ, self.validate)?;
+            self.store(output);
+            owned(fromcell(&[coefficients])?).remove(0)
+        };
+        self.report(&coefficients)
+    }
+
+    // The totals of every row on both sides for the coefficients of the parsed equation.
+    fn report(&self, coefficients: &[T]) -> Result<Verification<T>, ErrorCases> {
+        if coefficients.len() != self.cd.sum {
+            return Err(ErrorCases::CoefficientCount(
+                self.cd.sum,
+                coefficients.len(),
+            ));
+        }
+        let matrix = owned(fromcell(&self.ds["Parser"])?);
+        let rows = totals((&self.cd, &matrix), coefficients)?
+            .into_iter()
+            .zip(self.elements.iter())
+            .map(|((left, right), element)| {
+                Ok(Residual {
+                    element: element.clone(),
+                    residual: safe_calc(&left, &right, &Operator::Sub)?,
+                    left,
+                    right,
+                })
+            })
+            .collect::<Result<Vec<_>, ErrorCases>>()?;
+        let mass = if self.nuclear {
+            None
+        } else {
+            self.masses(coefficients)
+        };
+        Ok(Verification {
+            coefficients: self
+                .cd
+                .species
+                .iter()
+                .cloned()
+                .zip(coefficients.iter().cloned())
+                .collect::<Vec<_>>(),
+            rows,
+            mass,
+        })
     }
 
     /// Parse the equation
-    pub fn parse(&mut self) -> Result<DataSet<&T>, ErrorCases> {
-        match parser::<Cell<T>>(self.equ)? {
-            (cd, data) => {
-                self.cd = cd;
-                self.ds.insert("Parser", data);
+    pub fn parse(&mut self) -> Result<DataSet<'_, &T>, ErrorCases> {
+        let output = if self.nuclear {
+            nuclear_parser::<Cell<T>>(self.equ)?
+        } else {
+            parser::<Cell<T>>(self.equ, self.validate)?
+        };
+        self.store(output);
+        Ok((&self.cd, fromcell(&self.ds["Parser"])?))
+    }
+
+    /// The element (or `"e"` for the electronic charge) of each row of the parsed matrix.
+    ///
+    /// Elements are sorted by their first appearance in the equation. It is empty before the equation is parsed.
+    pub fn elements(&self) -> &[String] {
+        &self.elements
+    }
+
+    // Keep the parsed equation
+    fn store(&mut self, output: ParserOutput<Cell<T>>) {
+        let (cd, elements, data) = output;
+        self.cd = cd;
+        self.elements = elements;
+        self.ds.insert("Parser", data);
+    }
+
+    // The molar masses of both sides, if every chemical formula has one.
+    fn masses(&self, coefficients: &[T]) -> Option<(f64, f64)> {
+        let (mut left, mut right) = (0.0, 0.0);
+        for (j, (species, c)) in self.cd.species.iter().zip(coefficients).enumerate() {
+            // `CheckedType` can't be converted to a float, but it can be printed.
+            let mass = molar_mass(species).ok()? * c.to_string().parse::<f64>().ok()?;
+            if j < self.cd.left {
+                left += mass;
+            } else {
+                right += mass;
             }
         }
-        Ok((&self.cd, fromcell(&self.ds["Parser"])?))
+        Some((left, right))
     }
 
     // Balance the equation
     fn balance(&mut self) -> Result<(), ErrorCases> {
-        self.ds.insert(
-            "Balancer",
-            balancer::<Cell<T>>((&self.cd, &self.ds["Parser"]))?,
-        );
+        let matrix = owned(fromcell(&self.ds["Parser"])?);
+        self.basis = balancer::<T>((&self.cd, &matrix), self.solver.as_ref())?;
         Ok(())
     }
 }
+
+/// A handler which balances the equation with `i32`, and retries with a wider integer type whenever it overflows.
+///
+/// Unlike `Handler`, it has no integer type and no solver of its own, since it tries several types
+/// and a solver only works on its own integer type. Every type is balanced with `solvers::GaussJordan`.
+pub struct AutoHandler<'a> {
+    equ: &'a str,
+    nuclear: bool,
+    validate: bool,
+}
+
+impl<'a> AutoHandler<'a> {
+    /// Create an `AutoHandler` by given equation
+    pub fn new(equ: &'a str) -> Self {
+        AutoHandler {
+            equ,
+            nuclear: false,
+            validate: false,
+        }
+    }
+
+    /// Balance a nuclear reaction. See `Handler::nuclear`.
+    pub fn nuclear(mut self, nuclear: bool) -> Self {
+        self.nuclear = nuclear;
+        self
+    }
+
+    /// Check every element symbol against the periodic table. See `Handler::validate`.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Balance the equation like `Handler::handle`, with the first integer type which doesn't overflow.
+    ///
+    /// The types are tried in the order of `i32`, `i64`, `i128` and `num::BigInt` (only with the `bigint` feature),
+    /// each one with the same options. Errors other than `ErrorCases::Overflow` are returned at once.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lib_xch::public::{handler::AutoHandler, structs::Width};
+    ///
+    /// let r = AutoHandler::new("U235+n=Ba141+Kr92+3n").nuclear(true).handle().unwrap();
+    /// assert_eq!(r.width(), Width::I32);
+    /// ```
+    pub fn handle(&self) -> Result<AutoBalanceResult, ErrorCases> {
+        match self.handler::<i32>().handle() {
+            Err(ErrorCases::Overflow) => (),
+            r => return r.map(AutoBalanceResult::I32),
+        }
+        match self.handler::<i64>().handle() {
+            Err(ErrorCases::Overflow) => (),
+            r => return r.map(AutoBalanceResult::I64),
+        }
+        #[cfg(feature = "bigint")]
+        {
+            match self.handler::<i128>().handle() {
+                Err(ErrorCases::Overflow) => (),
+                r => return r.map(AutoBalanceResult::I128),
+            }
+            self.handler::<num::BigInt>()
+                .handle()
+                .map(AutoBalanceResult::BigInt)
+        }
+        #[cfg(not(feature = "bigint"))]
+        self.handler::<i128>().handle().map(AutoBalanceResult::I128)
+    }
+
+    // A `Handler` of the integer type, with the same equation and options
+    fn handler<T: CheckedType + CheckedCalc>(&self) -> Handler<'a, T> {
+        Handler::<T>::new(self.equ)
+            .nuclear(self.nuclear)
+            .validate(self.validate)
+    }
+}
+
+/// Balance the equation with `i32`, and retry with a wider integer type whenever it overflows. See `AutoHandler`.
+///
+/// # Example
+///
+/// ```
+/// use lib_xch::public::{handler::handle_auto, structs::Width};
+///
+/// let r = handle_auto("(A2147483647)2+A=A").unwrap();
+/// assert_eq!(r.width(), Width::I64);
+/// ```
+pub fn handle_auto(equ: &str) -> Result<AutoBalanceResult, ErrorCases> {
+    AutoHandler::new(equ).handle()
+}
+
+/// Check the coefficients against the equation. See `Handler::verify`.
+///
+/// # Example
+///
+/// ```
+/// use lib_xch::public::handler::verify;
+///
+/// let v = verify::<i32>("H2+O2=H2O", &[1, 1, 1]).unwrap();
+/// assert!(!v.is_balanced());
+/// let o = v.rows.iter().find(|r| r.element == "O").unwrap();
+/// assert_eq!((o.left, o.right, o.residual), (2, 1, 1));
+/// ```
+pub fn verify<T: CheckedType + CheckedCalc>(
+    equ: &str,
+    coefficients: &[T],
+) -> Result<Verification<T>, ErrorCases> {
+    Handler::<T>::new(equ).verify(coefficients)
+}
+
+/// Read the leading numbers of the equation as the coefficients and check them. See `Handler::check`.
+///
+/// # Example
+///
+/// ```
+/// use lib_xch::public::handler::check;
+///
+/// let v = check::<i32>("2H2+O2=H2O").unwrap();
+/// let o = v.rows.iter().find(|r| r.element == "O").unwrap();
+/// assert_eq!((o.left, o.right, o.residual), (2, 1, 1));
+/// ```
+pub fn check<T: CheckedType + CheckedCalc>(equ: &str) -> Result<Verification<T>, ErrorCases> {
+    Handler::<T>::new(equ).check()
+}
+
 // All `false` => `true` (It didn't overflow)
 fn check_tag<T>(v: &[Vec<Cell<T>>]) -> bool {
     v.iter().all(|x| x.iter().all(|x| !x.get_tag()))
@@ -104,3 +477,9 @@ fn fromcell<T>(v: &[Vec<Cell<T>>]) -> Result<Vec<Vec<&T>>, ErrorCases> {
         .map(|x| x.iter().map(Cell::get_data).collect::<Vec<_>>())
         .collect::<Vec<_>>())
 }
+
+fn owned<T: Clone>(v: Vec<Vec<&T>>) -> Vec<Vec<T>> {
+    v.into_iter()
+        .map(|x| x.into_iter().cloned().collect::<Vec<_>>())
+        .collect::<Vec<_>>()
+}
@@ -0,0 +1,61 @@
+// Copyright 2017-2019 LEXUGE
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The molar mass and the percent composition of a chemical formula.
+//!
+//! The formula is read by the same parser as the equations, so hydrates (`CuSO4.5H2O`), fractional subscripts,
+//! isotopes (`[13C]`, `D`) and a leading multiplier are all accepted. The element symbols must be in the periodic table.
+
+// inside use(s)
+use super::{
+    failures::ErrorCases,
+    structs::{Composition, ElementMass},
+};
+use crate::parser::{elements::atomic_weight, handler::formula_parser};
+
+/// Compute the molar mass of the chemical formula in g/mol, and how much of it each element makes up.
+///
+/// The mass of the electrons is ignored, so an ion weighs the same as its neutral formula.
+pub fn composition(formula: &str) -> Result<Composition, ErrorCases> {
+    let atoms = formula_parser::<i64>(formula)?;
+    let elements = atoms
+        .into_iter()
+        .filter(|(element, _)| element != "e")
+        .map(|(element, count)| {
+            let count = *count.numer() as f64 / *count.denom() as f64;
+            // Every symbol has been checked against the periodic table.
+            let mass = atomic_weight(&element).unwrap_or(0.0) * count;
+            (element, count, mass)
+        })
+        .collect::<Vec<_>>();
+    let molar_mass = elements.iter().map(|(_, _, mass)| mass).sum::<f64>();
+    Ok(Composition {
+        molar_mass,
+        elements: elements
+            .into_iter()
+            .map(|(element, count, mass)| ElementMass {
+                element,
+                count,
+                mass,
+                percent: mass / molar_mass * 100.0,
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Compute the molar mass of the chemical formula in g/mol. See `composition`.
+pub fn molar_mass(formula: &str) -> Result<f64, ErrorCases> {
+    composition(formula).map(|c| c.molar_mass)
+}
@@ -15,9 +15,12 @@
 
 //! Public structs
 
+use num::Zero;
+use std::fmt::{Display, Error, Formatter};
+
 // This struct is required to formating the equation
 /// Description of the chemical equation
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct ChemicalEquation {
     /// The number of the chemical formulas of the left side.
     pub left: usize,
@@ -25,6 +28,12 @@ pub struct ChemicalEquation {
     pub right: usize,
     /// The sum of all the chemical formulas in the equation.
     pub sum: usize,
+    /// The chemical formulas as they are written, from left to right.
+    pub species: Vec<String>,
+    /// The physical state annotation of each chemical formula, if it is given. It is ignored while balancing.
+    pub states: Vec<Option<State>>,
+    /// The arrow between the two sides.
+    pub arrow: Arrow,
 }
 
 impl ChemicalEquation {
@@ -33,6 +42,317 @@ impl ChemicalEquation {
             left: 0,
             right: 0,
             sum: 0,
+            species: Vec::new(),
+            states: Vec::new(),
+            arrow: Arrow::Equal,
+        }
+    }
+
+    /// The chemical formulas of the left side.
+    pub fn left_species(&self) -> &[String] {
+        &self.species[..self.left]
+    }
+
+    /// The chemical formulas of the right side.
+    pub fn right_species(&self) -> &[String] {
+        &self.species[self.left..]
+    }
+}
+
+impl Display for ChemicalEquation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        let formulas = self
+            .species
+            .iter()
+            .zip(self.states.iter())
+            .map(|(s, state)| match state {
+                Some(state) => format!("{}{}", s, state),
+                None => s.to_string(),
+            })
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "{}{}{}",
+            formulas[..self.left].join("+"),
+            self.arrow,
+            formulas[self.left..].join("+")
+        )
+    }
+}
+
+/// The physical state annotation of a chemical formula.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    /// `(s)`
+    Solid,
+    /// `(l)`
+    Liquid,
+    /// `(g)`
+    Gas,
+    /// `(aq)`
+    Aqueous,
+}
+
+impl State {
+    /// The annotation as it is written.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            State::Solid => "(s)",
+            State::Liquid => "(l)",
+            State::Gas => "(g)",
+            State::Aqueous => "(aq)",
+        }
+    }
+
+    pub(crate) fn from_symbol(s: &str) -> Option<Self> {
+        [State::Solid, State::Liquid, State::Gas, State::Aqueous]
+            .iter()
+            .find(|state| state.as_str() == s)
+            .cloned()
+    }
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The arrow between the two sides of a chemical equation, as it is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Arrow {
+    /// `=`
+    Equal,
+    /// `->`
+    Forward,
+    /// `→`
+    Rightwards,
+    /// `<->`
+    Reversible,
+    /// `<=>`
+    Equilibrium,
+    /// `⇌`
+    Harpoons,
+}
+
+impl Arrow {
+    /// The arrow as it is written.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Arrow::Equal => "=",
+            Arrow::Forward => "->",
+            Arrow::Rightwards => "→",
+            Arrow::Reversible => "<->",
+            Arrow::Equilibrium => "<=>",
+            Arrow::Harpoons => "⇌",
+        }
+    }
+
+    /// The direction of the reaction which the arrow means.
+    pub fn direction(self) -> Direction {
+        match self {
+            Arrow::Equal | Arrow::Forward | Arrow::Rightwards => Direction::Forward,
+            Arrow::Reversible => Direction::Reversible,
+            Arrow::Equilibrium | Arrow::Harpoons => Direction::Equilibrium,
+        }
+    }
+
+    pub(crate) fn from_symbol(s: &str) -> Option<Self> {
+        [
+            Arrow::Equal,
+            Arrow::Forward,
+            Arrow::Rightwards,
+            Arrow::Reversible,
+            Arrow::Equilibrium,
+            Arrow::Harpoons,
+        ]
+        .iter()
+        .find(|a| a.as_str() == s)
+        .cloned()
+    }
+}
+
+impl Display for Arrow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The direction of a reaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// The reaction goes from the left side to the right side.
+    Forward,
+    /// The reaction can go either way.
+    Reversible,
+    /// The two sides are in equilibrium.
+    Equilibrium,
+}
+
+/// The owned result of balancing a chemical equation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BalanceResult<T> {
+    /// Description of the equation, including the chemical formulas and how they are grouped.
+    pub equation: ChemicalEquation,
+    /// The elements (and `"e"` for the electronic charge), one for each row of `matrix`, sorted by their first appearance.
+    ///
+    /// Isotopes are written as `[13C]`. In the nuclear mode, they are the mass number `"A"` and the atomic number `"Z"`.
+    pub elements: Vec<String>,
+    /// The composition matrix. Rows are `elements`, columns are the chemical formulas.
+    ///
+    /// The columns of the right side are negated. A row with fractional subscripts is scaled to integers.
+    pub matrix: Vec<Vec<T>>,
+    /// The set of Basic Solutions, each one labelled by the chemical formulas.
+    pub solutions: Vec<Vec<(String, T)>>,
+    /// The chemical formulas which can't take part in the reaction as written.
+    pub warnings: Vec<Warning>,
+}
+
+impl<T: Clone> BalanceResult<T> {
+    /// The set of Basic Solutions without labels.
+    pub fn coefficients(&self) -> Vec<Vec<T>> {
+        self.solutions
+            .iter()
+            .map(|s| s.iter().map(|(_, v)| v.clone()).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    }
+}
+
+/// A warning about a chemical formula of a balanced equation.
+///
+/// The first field is the index of the chemical formula in `ChemicalEquation::species`,
+/// so that the same chemical formula written twice can be told apart. The second one is the chemical formula.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// The coefficient is zero in every solution, so the chemical formula is a spectator or can't be reached.
+    AlwaysZero(usize, String),
+    /// The coefficient can't be positive in any solution whose coefficients are all non-negative,
+    /// but it can be if the chemical formula is moved to the other side.
+    WrongSide(usize, String),
+}
+
+/// Why an equation can't be balanced.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// The elements (or `"e"` for the electronic charge) which only appear on one side of the equation.
+    pub one_sided_elements: Vec<String>,
+    /// The chemical formulas whose coefficients are zero in every solution.
+    pub zero_species: Vec<String>,
+    /// A minimal subset of the elements whose conservation alone forces all of `zero_species` to zero.
+    ///
+    /// Dropping any one of them would let some of `zero_species` be non-zero. It is empty if `zero_species` is empty.
+    pub inconsistent_elements: Vec<String>,
+}
+
+impl Diagnosis {
+    /// Whether nothing is wrong with the equation.
+    pub fn is_empty(&self) -> bool {
+        self.one_sided_elements.is_empty()
+            && self.zero_species.is_empty()
+            && self.inconsistent_elements.is_empty()
+    }
+}
+
+/// The totals of a row of the composition matrix on both sides of a chemical equation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Residual<T> {
+    /// The element, or `"e"` for the electronic charge.
+    pub element: String,
+    /// The total on the left side.
+    pub left: T,
+    /// The total on the right side.
+    pub right: T,
+    /// `left - right`, which is zero if the row is conserved.
+    pub residual: T,
+}
+
+/// The report of checking a set of coefficients against a chemical equation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Verification<T> {
+    /// The coefficients which have been checked, each one labelled by the chemical formula.
+    pub coefficients: Vec<(String, T)>,
+    /// One for each element, in the same order as `BalanceResult::elements`.
+    ///
+    /// A row with fractional subscripts is scaled to integers, like `BalanceResult::matrix`.
+    pub rows: Vec<Residual<T>>,
+    /// The molar masses of the left side and the right side in g/mol.
+    ///
+    /// It is `None` in the nuclear mode, or if any element symbol isn't in the periodic table.
+    pub mass: Option<(f64, f64)>,
+}
+
+impl<T: Zero> Verification<T> {
+    /// Whether every row is conserved, and so is the molar mass up to the rounding errors.
+    ///
+    /// A zero coefficient leaves its chemical formula out, so it is never balanced, e.g. `0H2+0O2=0H2O` isn't.
+    pub fn is_balanced(&self) -> bool {
+        self.coefficients.iter().all(|(_, c)| !c.is_zero())
+            && self.rows.iter().all(|r| r.residual.is_zero())
+            && self.mass.is_none_or(|(left, right)| {
+                (left - right).abs() <= 1e-9 * left.abs().max(right.abs())
+            })
+    }
+}
+
+/// The molar mass of a chemical formula and the share of each element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Composition {
+    /// The molar mass in g/mol.
+    pub molar_mass: f64,
+    /// The elements, sorted by their first appearance.
+    pub elements: Vec<ElementMass>,
+}
+
+/// The share of an element in the molar mass of a chemical formula.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElementMass {
+    /// The element. Isotopes are written as `[13C]`.
+    pub element: String,
+    /// The number of atoms. It may be fractional, like in `Fe0.95O`.
+    pub count: f64,
+    /// The mass of these atoms in g/mol.
+    pub mass: f64,
+    /// The percentage of the molar mass, from 0 to 100.
+    pub percent: f64,
+}
+
+/// The integer type which the equation has been balanced with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Width {
+    /// `i32`
+    I32,
+    /// `i64`
+    I64,
+    /// `i128`
+    I128,
+    /// `num::BigInt`
+    #[cfg(feature = "bigint")]
+    BigInt,
+}
+
+/// The result of `AutoHandler::handle`, in the integer type which succeeded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AutoBalanceResult {
+    /// Balanced with `i32`.
+    I32(BalanceResult<i32>),
+    /// Balanced with `i64`.
+    I64(BalanceResult<i64>),
+    /// Balanced with `i128`.
+    I128(BalanceResult<i128>),
+    /// Balanced with `num::BigInt`.
+    #[cfg(feature = "bigint")]
+    BigInt(BalanceResult<num::BigInt>),
+}
+
+impl AutoBalanceResult {
+    /// The integer type which succeeded.
+    pub fn width(&self) -> Width {
+        match self {
+            AutoBalanceResult::I32(_) => Width::I32,
+            AutoBalanceResult::I64(_) => Width::I64,
+            AutoBalanceResult::I128(_) => Width::I128,
+            #[cfg(feature = "bigint")]
+            AutoBalanceResult::BigInt(_) => Width::BigInt,
         }
     }
 }
@@ -15,16 +15,18 @@
 
 //! The traits which may be useful.
 
-use nalgebra::base::Scalar;
 use num::{
     traits::{
-        ops::checked::{CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedRem, CheckedSub},
+        ops::checked::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub},
         Num, NumAssign,
     },
-    Integer, Signed,
+    Integer, Signed, Zero,
 };
 // inside use(s)
-use std::{fmt::Display, marker::Copy, str::FromStr};
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
 
 // marcos for auto-creating implementations
 macro_rules! checked_calc_impl {
@@ -40,15 +42,13 @@ macro_rules! checked_type_impl {
 }
 
 /// The trait which must be implemented when using `safe_calc()`
-pub trait CheckedCalc:
-    CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + CheckedRem + CheckedNeg
-{
+pub trait CheckedCalc: CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + Zero {
     // Empty
 }
 
 /// The trait which must be implemented.
 pub trait CheckedType:
-    Num + Copy + FromStr + Integer + Scalar + Signed + NumAssign + Display + Clone
+    Num + FromStr + Integer + Signed + NumAssign + Display + Debug + Clone
 {
     // Empty
 }
@@ -74,3 +74,9 @@ checked_type_impl!(i32);
 checked_type_impl!(i64);
 checked_type_impl!(i128);
 checked_type_impl!(isize);
+
+// Arbitrary-precision integers never overflow
+#[cfg(feature = "bigint")]
+checked_calc_impl!(num::BigInt);
+#[cfg(feature = "bigint")]
+checked_type_impl!(num::BigInt);
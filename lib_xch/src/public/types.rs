@@ -21,3 +21,5 @@ use super::structs::ChemicalEquation;
 // type aliases
 pub type DataSet<'a, T> = (&'a ChemicalEquation, Vec<Vec<T>>);
 pub(crate) type DataSetIn<'a, T> = (&'a ChemicalEquation, &'a Vec<Vec<T>>); // Use inside for less allocations
+pub(crate) type ParserOutput<T> = (ChemicalEquation, Vec<String>, Vec<Vec<T>>); // Description, elements and the table
+pub(crate) type DiagnoseOutput = (Vec<usize>, Vec<usize>, Vec<usize>); // One-sided rows, zero columns and the minimal rows
@@ -15,9 +15,18 @@
 
 mod testers;
 
-use lib_xch::public::failures::ErrorCases::{Overflow, ParserError, ZeroSolution};
+use lib_xch::public::{
+    failures::{
+        ErrorCases,
+        ErrorCases::{NoPositiveSolution, Overflow, ZeroSolution},
+    },
+    handler::{check, handle_auto, verify, AutoHandler, Handler},
+    mass::{composition, molar_mass},
+    solvers::{GaussJordan, Modular, NullSpaceSolver},
+    structs::{Arrow, AutoBalanceResult, Direction, State, Warning, Width},
+};
 // inside use(s)
-use crate::testers::{tester, tester_error};
+use crate::testers::{parse_error, tester, tester_error};
 
 #[test]
 fn solve() {
@@ -134,18 +143,915 @@ fn solve() {
 
 #[test]
 fn error() {
-    tester_error::<i32>("AAA", &ParserError(" --> 1:4\n  |\n1 | AAA\n  |    ^---\n  |\n  = expected num, atom_name, electron, or parenthesis_wrapper".to_string()));
     tester_error::<i32>(
-        "AAAA==",
-        &ParserError(
-            " --> 1:6\n  |\n1 | AAAA==\n  |      ^---\n  |\n  = expected molecule".to_string(),
+        "AAA",
+        &parse_error(
+            3..3,
+            &[
+                "a number",
+                "a fraction",
+                "an atom",
+                "a charge",
+                "a bracket",
+                "a state",
+                "an arrow",
+            ],
+            None,
+            None,
         ),
     );
+    tester_error::<i32>(
+        "AAAA==",
+        &parse_error(5..6, &["a chemical formula"], Some("="), None),
+    );
+    // The superscripts have names of their own too.
+    tester_error::<i32>(
+        "A³=A",
+        &parse_error(2..3, &["a superscript sign"], Some("="), None),
+    );
     tester_error::<i32>(
         "/A=A*",
-        &ParserError(" --> 1:1\n  |\n1 | /A=A*\n  | ^---\n  |\n  = expected molecule".to_string()),
+        &parse_error(0..1, &["a chemical formula"], Some("/"), None),
+    );
+    // The positions are in the equation, even inside a chemical formula.
+    tester_error::<i32>(
+        "H₂+Ni1/0=Ni",
+        &parse_error(5..8, &[], Some("1/0"), Some("The denominator is zero")),
+    );
+    tester_error::<i32>(
+        "co+O2=CO2",
+        &parse_error(
+            0..1,
+            &["a chemical formula"],
+            Some("c"),
+            Some("Lowercase element symbol 'co' — did you mean 'Co' or 'CO'?"),
+        ),
+    );
+    tester_error::<i32>(
+        "H2+O2=h2o",
+        &parse_error(
+            6..7,
+            &["a chemical formula"],
+            Some("h"),
+            Some("Lowercase element symbol 'h' — did you mean 'H'?"),
+        ),
+    );
+    // The particles and the states are lowercase on purpose.
+    let hint = |equ: &str, nuclear: bool| match Handler::<i32>::new(equ).nuclear(nuclear).handle() {
+        Err(ErrorCases::ParserError(e)) => e.hint,
+        r => panic!("{:?}", r),
+    };
+    assert_eq!(hint("U235+nn=Ba141", true), None);
+    assert_eq!(hint("Fe^3++e=Fe^2+", false), None);
+    assert_eq!(hint("NaCl=(aq)", false), None);
+    assert_eq!(
+        hint("NaCl=(ag)", false),
+        Some("Lowercase element symbol 'ag' — did you mean 'Ag' or 'AG'?".to_string())
+    );
+    assert_eq!(
+        Handler::<i32>::new("AAAA==")
+            .handle()
+            .unwrap_err()
+            .to_string(),
+        "Unexpected '=' at 6, expected a chemical formula"
+    );
+    assert_eq!(
+        Handler::<i32>::new("Cu(OH=CuO+H2O")
+            .handle()
+            .unwrap_err()
+            .to_string(),
+        "Unexpected end of the equation at 14, expected ')'. '(' is opened at 3"
     );
     tester_error::<i32>("A=B", &ZeroSolution);
     tester_error::<i32>("((((A32767)32767)32767)32767)=A", &Overflow);
     tester_error::<i32>("(A2147483647)2+A=A", &Overflow);
 }
+
+#[test]
+fn balance_result() {
+    let r = Handler::<i32>::new("H2O=H2+O2").handle().unwrap();
+    assert_eq!(r.equation.left_species(), &["H2O".to_string()]);
+    assert_eq!(
+        r.equation.right_species(),
+        &["H2".to_string(), "O2".to_string()]
+    );
+    assert_eq!(
+        r.solutions,
+        vec![vec![
+            ("H2O".to_string(), 2),
+            ("H2".to_string(), 2),
+            ("O2".to_string(), 1)
+        ]]
+    );
+    assert_eq!(r.elements, ["H", "O", "e"]);
+    assert_eq!(
+        r.matrix,
+        vec![vec![2, -2, 0], vec![1, 0, -2], vec![0, 0, 0]]
+    );
+}
+
+#[test]
+fn elements_order() {
+    let mut handler = Handler::<i32>::new("K4Fe(CN)6+H2SO4+H2O=K2SO4+FeSO4+(NH4)2SO4+CO");
+    let matrix = handler
+        .parse()
+        .unwrap()
+        .1
+        .iter()
+        .map(|x| x.iter().map(|x| **x).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        handler.elements(),
+        ["K", "Fe", "C", "N", "e", "H", "S", "O"]
+    );
+    assert_eq!(
+        matrix,
+        vec![
+            vec![4, 0, 0, -2, 0, 0, 0],
+            vec![1, 0, 0, 0, -1, 0, 0],
+            vec![6, 0, 0, 0, 0, 0, -1],
+            vec![6, 0, 0, 0, 0, -2, 0],
+            vec![0, 0, 0, 0, 0, 0, 0],
+            vec![0, 2, 2, 0, 0, -8, 0],
+            vec![0, 1, 0, -1, -1, -1, 0],
+            vec![0, 4, 1, -4, -4, -4, -1],
+        ]
+    );
+}
+
+#[test]
+fn minimal_positive() {
+    let coefficients = |equ: &str| {
+        Handler::<i32>::new(equ)
+            .minimal_positive()
+            .map(|v| v.into_iter().map(|(_, c)| c).collect::<Vec<_>>())
+    };
+    assert_eq!(coefficients("H2O=H2+O2"), Ok(vec![2, 2, 1]));
+    assert_eq!(
+        coefficients("KClO3+HCl=KCl+ClO2+Cl2+H2O"),
+        Ok(vec![2, 4, 2, 2, 1, 2])
+    );
+    assert_eq!(
+        coefficients("Cu+HNO3=Cu(NO3)2+NO+NO2+H2O"),
+        Ok(vec![2, 6, 2, 1, 1, 3])
+    );
+    assert_eq!(
+        coefficients("NH4ClO4+HNO3+HCl=HClO4+N2O+Cl2+H2O"),
+        Ok(vec![1, 3, 8, 1, 2, 4, 7])
+    );
+    assert_eq!(coefficients("A+A=B"), Err(NoPositiveSolution));
+    assert_eq!(coefficients("A=B"), Err(ZeroSolution));
+    // `A+B+C+D+E=AB+AC+...+DE`, whose null space has the dimension 10 or more
+    for n in 5..=8 {
+        let atoms = ["A", "B", "C", "D", "E", "F", "G", "H"][..n].to_vec();
+        let pairs = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .map(|(i, j)| format!("{}{}", atoms[i], atoms[j]))
+            .collect::<Vec<_>>();
+        let mut expected = vec![n as i32 - 1; n];
+        expected.extend(vec![1; pairs.len()]);
+        assert_eq!(
+            coefficients(&format!("{}={}", atoms.join("+"), pairs.join("+"))),
+            Ok(expected)
+        );
+    }
+}
+
+#[test]
+fn elementary_reactions() {
+    let coefficients = |equ: &str| {
+        Handler::<i32>::new(equ)
+            .elementary_reactions()
+            .unwrap()
+            .into_iter()
+            .map(|v| v.into_iter().map(|(_, c)| c).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        coefficients("Cu+HNO3=Cu(NO3)2+NO+NO2+H2O"),
+        vec![vec![1, 4, 1, 0, 2, 2], vec![3, 8, 3, 2, 0, 4]]
+    );
+    assert_eq!(
+        coefficients("KClO3+HCl=KCl+ClO2+Cl2+H2O"),
+        vec![vec![1, 6, 1, 0, 3, 3], vec![5, 6, 5, 6, 0, 3]]
+    );
+    assert_eq!(coefficients("H2O=H2+O2"), vec![vec![2, 2, 1]]);
+    assert_eq!(coefficients("A+A=B"), Vec::<Vec<i32>>::new());
+}
+
+#[test]
+fn arrows() {
+    for (equ, arrow, direction) in [
+        ("H2+O2=H2O", Arrow::Equal, Direction::Forward),
+        ("H2+O2->H2O", Arrow::Forward, Direction::Forward),
+        ("H2+O2→H2O", Arrow::Rightwards, Direction::Forward),
+        ("H2+O2<->H2O", Arrow::Reversible, Direction::Reversible),
+        ("H2+O2<=>H2O", Arrow::Equilibrium, Direction::Equilibrium),
+        ("H2+O2⇌H2O", Arrow::Harpoons, Direction::Equilibrium),
+    ]
+    .iter()
+    {
+        tester::<i32>(equ, &[&[2, 1, 2]]);
+        let r = Handler::<i32>::new(equ).handle().unwrap();
+        assert_eq!(r.equation.arrow, *arrow);
+        assert_eq!(r.equation.arrow.direction(), *direction);
+        // Round-trip
+        assert_eq!(r.equation.to_string(), *equ);
+        assert_eq!(
+            Handler::<i32>::new(&r.equation.to_string())
+                .handle()
+                .unwrap()
+                .equation,
+            r.equation
+        );
+    }
+    assert_eq!(
+        Handler::<i32>::new("Fe+Fe<3e+>->Fe<2e+>")
+            .handle()
+            .unwrap()
+            .equation
+            .arrow,
+        Arrow::Forward
+    );
+}
+
+#[test]
+fn states() {
+    let equ = "NaCl(aq)+AgNO3(aq)=AgCl(s)+NaNO3(aq)";
+    tester::<i32>(equ, &[&[1, 1, 1, 1]]);
+    let r = Handler::<i32>::new(equ).handle().unwrap();
+    assert_eq!(r.equation.species, ["NaCl", "AgNO3", "AgCl", "NaNO3"]);
+    assert_eq!(
+        r.equation.states,
+        [
+            Some(State::Aqueous),
+            Some(State::Aqueous),
+            Some(State::Solid),
+            Some(State::Aqueous)
+        ]
+    );
+    assert_eq!(r.equation.to_string(), equ);
+    let r = Handler::<i32>::new("H2(g) + O2 (g) -> H2O(l)")
+        .handle()
+        .unwrap();
+    assert_eq!(r.coefficients(), [[2, 1, 2]]);
+    assert_eq!(r.equation.to_string(), "H2(g)+O2(g)->H2O(l)");
+    tester::<i32>(
+        "Fe<3e+>(aq)+Cu(s)=Fe<2e+>(aq)+Cu<2e+>(aq)",
+        &[&[2, 1, 2, 1]],
+    );
+    // Sulfur in parentheses isn't a state.
+    tester::<i32>("H2+(S)=H2S", &[&[1, 1, 1]]);
+}
+
+#[test]
+fn isotopes() {
+    tester::<i32>("^2H2+O2=^2H2O", &[&[2, 1, 2]]);
+    // Each isotope is a row of its own.
+    let r = Handler::<i32>::new("[13C]O2+H2O=[13C]6H12O6+O2")
+        .handle()
+        .unwrap();
+    assert_eq!(r.elements, ["[13C]", "O", "e", "H"]);
+    assert_eq!(r.coefficients(), [[6, 6, 1, 6]]);
+    tester::<i32>("^2H2=[2H]", &[&[1, 2]]);
+    tester_error::<i32>("[2H]2=H2", &ZeroSolution);
+    // With the periodic table, `^2H`, `[2H]` and `D` are the same, and so are `[3H]` and `T`.
+    let validated = |equ: &str| Handler::<i32>::new(equ).validate(true).handle();
+    let r = validated("H2O+D2O=HDO").unwrap();
+    assert_eq!(r.elements, ["H", "O", "e", "[2H]"]);
+    assert_eq!(r.coefficients(), [[1, 1, 2]]);
+    for equ in ["D2=^2H2", "D2O=[2H]2O", "T2O=[3H]2O"] {
+        assert_eq!(validated(equ).unwrap().coefficients(), [[1, 1]]);
+    }
+    // Otherwise they are symbols like any other.
+    let r = Handler::<i32>::new("H2O+D2O=HDO").handle().unwrap();
+    assert_eq!(r.elements, ["H", "O", "e", "D"]);
+    assert_eq!(r.coefficients(), [[1, 1, 2]]);
+    tester_error::<i32>("D2O=[2H]2O", &ZeroSolution);
+}
+
+#[test]
+fn nuclear() {
+    let nuclear = |equ: &str| {
+        Handler::<i32>::new(equ)
+            .nuclear(true)
+            .handle()
+            .unwrap()
+            .coefficients()
+    };
+    assert_eq!(nuclear("U238=Th234+He4"), [[1, 1, 1]]);
+    assert_eq!(nuclear("[14C]->[14N]+e"), [[1, 1, 1]]);
+    assert_eq!(nuclear("^3H=^3He+e"), [[1, 1, 1]]);
+    let r = Handler::<i32>::new("U235+n=Ba141+Kr92+3n")
+        .nuclear(true)
+        .handle()
+        .unwrap();
+    assert_eq!(r.elements, ["A", "Z"]);
+    assert_eq!(r.matrix, [[235, 1, -141, -92, -3], [92, 0, -56, -36, 0]]);
+    let matrix = |equ: &str| {
+        Handler::<i32>::new(equ)
+            .nuclear(true)
+            .handle()
+            .unwrap()
+            .matrix
+    };
+    assert_eq!(matrix("D+T=He4+n"), [[2, 3, -4, -1], [1, 1, -2, 0]]);
+    assert_eq!(matrix("Be9+He4=C12+n"), [[9, 4, -12, -1], [4, 2, -6, 0]]);
+    assert_eq!(matrix("n=p+e"), [[1, -1, 0], [0, -1, 1]]);
+    assert_eq!(
+        Handler::<i32>::new("U+n=Ba141+Kr92+3n")
+            .nuclear(true)
+            .handle(),
+        Err(parse_error(
+            1..1,
+            &["a mass number"],
+            Some("+"),
+            Some("The mass number of 'U' is missing")
+        ))
+    );
+    assert_eq!(
+        Handler::<i32>::new("Xx235=Ba141+Kr92+3n")
+            .nuclear(true)
+            .handle(),
+        Err(parse_error(
+            0..2,
+            &["an element symbol"],
+            Some("Xx"),
+            Some("Unknown element 'Xx'")
+        ))
+    );
+}
+
+#[test]
+fn charges() {
+    let matrix = |equ: &str| Handler::<i32>::new(equ).handle().unwrap().matrix;
+    let expected = matrix("Fe<3e+>+Cu=Fe<2e+>+Cu<2e+>");
+    for equ in [
+        "Fe^3++Cu=Fe^2++Cu^2+",
+        "Fe^{3+}+Cu=Fe^{2+}+Cu^{2+}",
+        "Fe+3+Cu=Fe+2+Cu+2",
+        "Fe{3+}+Cu=Fe{2+}+Cu{2+}",
+        "Fe³⁺+Cu=Fe²⁺+Cu²⁺",
+        "(Fe)3++Cu=(Fe)2++(Cu)2+",
+        "Fe^3+ + Cu = Fe+2 + Cu{2+}",
+    ]
+    .iter()
+    {
+        assert_eq!(matrix(equ), expected);
+        tester::<i32>(equ, &[&[2, 1, 2, 1]]);
+    }
+    let expected = matrix("Fe(CN)6<3e->+Cl<e->=Fe(CN)6<4e->+Cl");
+    for equ in [
+        "(Fe(CN)6)3-+Cl-1=(Fe(CN)6)4-+Cl",
+        "Fe(CN)6^3-+Cl^-=Fe(CN)6^{4-}+Cl",
+        "Fe(CN)6³⁻+Cl⁻=Fe(CN)6⁴⁻+Cl",
+    ]
+    .iter()
+    {
+        assert_eq!(matrix(equ), expected);
+    }
+    // A bare sign
+    let expected = matrix("Na<e+>+Cl<e->=NaCl");
+    for equ in [
+        "Na+(aq)+Cl-(aq)=NaCl(s)",
+        "Na+ + Cl- -> NaCl",
+        "Na++Cl-=NaCl",
+    ]
+    .iter()
+    {
+        assert_eq!(matrix(equ), expected);
+    }
+    tester::<i32>("NaCl(s)=Na+(aq)+Cl-(aq)", &[&[1, 1, 1]]);
+    tester::<i32>("Cu+Ag+=Cu^2++Ag", &[&[1, 2, 1, 2]]);
+    // They are not charges.
+    tester::<i32>("Fe+3Cl2=FeCl3", &[&[2, 1, 2]]);
+    tester::<i32>("(NH4)2+SO4=(NH4)2SO4", &[&[1, 1, 1]]);
+    tester::<i32>("Fe+3(aq)+Cu(s)=Fe+2(aq)+Cu+2(aq)", &[&[2, 1, 2, 1]]);
+}
+
+#[test]
+fn brackets() {
+    tester::<i32>(
+        "K4[Fe(CN)6]+H2SO4+H2O=K2SO4+FeSO4+(NH4)2SO4+CO",
+        &[&[1, 6, 6, 2, 1, 3, 6]],
+    );
+    tester::<i32>("CuSO4+NH3=[Cu(NH3)4]SO4", &[&[1, 4, 1]]);
+    tester::<i32>("CoCl3+NH3={[Co(NH3)6]}Cl3", &[&[1, 6, 1]]);
+    let matrix = |equ: &str| Handler::<i32>::new(equ).handle().unwrap().matrix;
+    assert_eq!(
+        matrix("[Fe(CN)6]3-+Cl-1=[Fe(CN)6]4-+Cl"),
+        matrix("Fe(CN)6<3e->+Cl<e->=Fe(CN)6<4e->+Cl")
+    );
+    assert_eq!(
+        matrix("{Fe(CN)6}{3-}+Cl^-=[Fe(CN)6]^{4-}+Cl"),
+        matrix("Fe(CN)6<3e->+Cl<e->=Fe(CN)6<4e->+Cl")
+    );
+    // Still an isotope
+    assert_eq!(
+        Handler::<i32>::new("[13C]O2=[13C]+O2")
+            .handle()
+            .unwrap()
+            .elements,
+        ["[13C]", "O", "e"]
+    );
+    // Brackets in the comments are ignored.
+    tester::<i32>("H2+O2/* ( */=H2O", &[&[2, 1, 2]]);
+    tester_error::<i32>(
+        "[Cu(NH3)4)SO4=CuSO4+NH3",
+        &parse_error(9..10, &["']'"], Some(")"), Some("'[' is opened at 1")),
+    );
+    tester_error::<i32>(
+        "Cu(OH=CuO+H2O",
+        &parse_error(13..13, &["')'"], None, Some("'(' is opened at 3")),
+    );
+    tester_error::<i32>(
+        "CuOH)2=CuO+H2O",
+        &parse_error(4..5, &[], Some(")"), Some("No bracket is opened before it")),
+    );
+}
+
+#[test]
+fn unicode() {
+    tester::<i32>("H₂+O₂=H₂O", &[&[2, 1, 2]]);
+    tester::<i32>("Al₂(SO₄)₃+NaOH=Na₂SO₄+Al(OH)₃", &[&[1, 6, 3, 2]]);
+    let matrix = |equ: &str| Handler::<i32>::new(equ).handle().unwrap().matrix;
+    let expected = matrix("CuSO4.5H2O=CuSO4+H2O");
+    for equ in [
+        "CuSO₄·5H₂O=CuSO₄+H₂O",
+        "CuSO4•5H2O=CuSO4+H2O",
+        "CuSO4∙5H2O=CuSO4+H2O",
+        "CuSO4⋅5H2O=CuSO4+H2O",
+    ]
+    .iter()
+    {
+        assert_eq!(matrix(equ), expected);
+        tester::<i32>(equ, &[&[1, 1, 5]]);
+    }
+    assert_eq!(matrix("SO₄²⁻+Ba²⁺=BaSO₄"), matrix("SO4<2e->+Ba<2e+>=BaSO4"));
+}
+
+#[test]
+fn fractional() {
+    tester::<i32>("Fe0.95O+O2=Fe2O3", &[&[80, 17, 38]]);
+    tester::<i32>(
+        "LiOH+NiO+MnO+CoO+O2=LiNi1/3Mn1/3Co1/3O2+H2O",
+        &[&[12, 4, 4, 4, 3, 12, 6]],
+    );
+    tester::<i32>("(Fe0.5)2O=FeO", &[&[1, 1]]);
+    // The rows are scaled to integers.
+    let result = Handler::<i32>::new("Fe0.95O+O2=Fe2O3").handle().unwrap();
+    assert_eq!(result.matrix[0], vec![19, 0, -40]);
+    // A decimal which nothing else follows
+    tester::<i32>("Fe2O2.5=Fe+O2", &[&[4, 8, 5]]);
+    tester::<i32>("YBa2Cu3O6.95+O2=YBa2Cu3O7", &[&[40, 1, 40]]);
+    // The hydrate dot isn't a decimal point.
+    tester::<i32>("CuSO4.5H2O=CuSO4+H2O", &[&[1, 1, 5]]);
+    tester::<i32>("CaSO4.0.5H2O=CaSO4+H2O", &[&[2, 2, 1]]);
+    tester::<i32>("CuSO4.5 H2O=CuSO4+H2O", &[&[1, 1, 5]]);
+    tester::<i32>("CaCl2.8NH3=CaCl2+NH3", &[&[1, 1, 8]]);
+    tester::<i32>("Al2O3.2SiO2.2H2O=Al2O3+SiO2+H2O", &[&[1, 1, 2, 2]]);
+    // But nobody writes the subscript `1` before a hydrate dot, so such a `.` is ambiguous.
+    let ambiguous = |span, found: &str, fraction: &str| {
+        parse_error(
+            span,
+            &[],
+            Some(found),
+            Some(&format!(
+                "'{}' may be a decimal or a hydrate — write the subscript as a fraction like '{}', or use '·' for a hydrate",
+                found, fraction
+            )),
+        )
+    };
+    tester_error::<i32>("Fe1.5O=Fe+O2", &ambiguous(2..5, "1.5", "3/2"));
+    tester_error::<i32>(
+        "Li1.2Mn0.54Ni0.13Co0.13O2=Li+Mn+Ni+Co+O2",
+        &ambiguous(2..5, "1.2", "6/5"),
+    );
+    assert_eq!(
+        molar_mass("Li1.2Mn0.54Ni0.13Co0.13O2"),
+        Err(ambiguous(2..5, "1.2", "6/5"))
+    );
+    tester::<i32>("Fe3/2O=Fe+O2", &[&[2, 3, 1]]);
+    tester::<i32>("Fe1·5O=Fe+O", &[&[1, 1, 5]]);
+    tester_error::<i32>(
+        "Ni1/0=Ni",
+        &parse_error(2..5, &[], Some("1/0"), Some("The denominator is zero")),
+    );
+}
+
+#[test]
+fn adversarial() {
+    // Too large for `i32` anywhere in the formula, never a panic.
+    for equ in [
+        "O999999999999=O",
+        "O₉₉₉₉₉₉₉₉₉₉₉₉=O",
+        "(((A65536)65536)65536)=A",
+        "65536(A65536)65536=A",
+        "A<99999999999e+>=A",
+        "A²¹⁴⁷⁴⁸³⁶⁴⁸⁺=A",
+        "A1/99999999999=A",
+        "A0.0000000000001=A",
+    ]
+    .iter()
+    {
+        tester_error::<i32>(equ, &Overflow);
+    }
+    for equ in ["U99999999999=U235", "99999999999n=n", "[99999999999U]=U"].iter() {
+        assert_eq!(
+            Handler::<i32>::new(equ).nuclear(true).handle(),
+            Err(Overflow)
+        );
+    }
+    assert_eq!(handle_auto("O999999999999=O").unwrap().width(), Width::I64);
+    // Just fits.
+    tester::<i32>("A2147483647B+A=B", &[&[1, -2147483647, 1]]);
+    tester::<i32>("(A0.5)2147483647=A", &[&[2, 2147483647]]);
+    let deep = |depth: usize| format!("{}A{}=A", "(".repeat(depth), ")".repeat(depth));
+    tester::<i32>(&deep(64), &[&[1, 1]]);
+    tester_error::<i32>(
+        &deep(300),
+        &parse_error(
+            64..65,
+            &[],
+            Some("("),
+            Some("Brackets can't be nested deeper than 64"),
+        ),
+    );
+}
+
+#[test]
+fn validate() {
+    let validated = |equ: &str| Handler::<i32>::new(equ).validate(true).handle();
+    // `CL` is read as `C` and `L` without the periodic table, so it can't balance.
+    tester_error::<i32>("NaCL+AgNO3=AgCl+NaNO3", &ZeroSolution);
+    assert_eq!(
+        validated("NaCL+AgNO3=AgCl+NaNO3"),
+        Err(parse_error(
+            2..4,
+            &["an element symbol"],
+            Some("CL"),
+            Some("Unknown element 'CL' — did you mean 'Cl'?")
+        ))
+    );
+    assert_eq!(
+        validated("MGO+H2O=Mg(OH)2"),
+        Err(parse_error(
+            0..2,
+            &["an element symbol"],
+            Some("MG"),
+            Some("Unknown element 'MG' — did you mean 'Mg'?")
+        ))
+    );
+    assert_eq!(
+        validated("Xx+O2=XxO2"),
+        Err(parse_error(
+            0..2,
+            &["an element symbol"],
+            Some("Xx"),
+            Some("Unknown element 'Xx' — did you mean 'Xe'?")
+        ))
+    );
+    assert_eq!(
+        validated("KMnO4+HCl=KCl+MnCl2+H2O+Cl2")
+            .unwrap()
+            .coefficients(),
+        vec![vec![2, 16, 2, 2, 8, 5]]
+    );
+    assert!(validated("D2O+[13C]O2=D2+[13C]O3").is_ok());
+    // The default is table-free.
+    tester::<i32>("A+B=AB", &[&[1, 1, 1]]);
+    assert!(validated("A+B=AB").is_err());
+}
+
+#[test]
+fn warnings() {
+    let warnings = |equ: &str| Handler::<i32>::new(equ).handle().unwrap().warnings;
+    assert_eq!(
+        warnings("A+A=B"),
+        vec![
+            Warning::AlwaysZero(2, "B".to_string()),
+            Warning::WrongSide(0, "A".to_string()),
+            Warning::WrongSide(1, "A".to_string()),
+        ]
+    );
+    assert_eq!(
+        warnings("H2O+H2=O2"),
+        vec![Warning::WrongSide(1, "H2".to_string())]
+    );
+    assert_eq!(warnings("H2+O2+Ar=H2O+Ar"), Vec::<Warning>::new());
+    assert_eq!(
+        warnings("KClO3+HCl=KCl+ClO2+Cl2+H2O"),
+        Vec::<Warning>::new()
+    );
+}
+
+#[test]
+fn diagnose() {
+    let diagnose = |equ: &str| Handler::<i32>::new(equ).diagnose().unwrap();
+    let names = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    let d = diagnose("A=B");
+    assert_eq!(d.one_sided_elements, names(&["A", "B"]));
+    assert_eq!(d.zero_species, names(&["A", "B"]));
+    assert_eq!(d.inconsistent_elements, names(&["A", "B"]));
+    // A typo
+    let d = diagnose("H2+O2=H2O+Xe");
+    assert_eq!(d.one_sided_elements, names(&["Xe"]));
+    assert_eq!(d.zero_species, names(&["Xe"]));
+    assert_eq!(d.inconsistent_elements, names(&["Xe"]));
+    // A missing product (O2)
+    let d = diagnose("KMnO4=K2MnO4+MnO2");
+    assert!(d.one_sided_elements.is_empty());
+    assert_eq!(d.zero_species, names(&["KMnO4", "K2MnO4", "MnO2"]));
+    assert_eq!(d.inconsistent_elements, names(&["K", "Mn", "O"]));
+    // Only NH3 can't take part in the reaction.
+    let d = diagnose("H2+O2+NH3=H2O");
+    assert_eq!(d.one_sided_elements, names(&["N"]));
+    assert_eq!(d.zero_species, names(&["NH3"]));
+    assert_eq!(d.inconsistent_elements, names(&["N"]));
+    assert!(diagnose("KMnO4=K2MnO4+MnO2+O2").is_empty());
+}
+
+#[test]
+fn custom_solver() {
+    // Doubles every Basic Solution of the default solver
+    struct Doubled;
+    impl NullSpaceSolver<i32> for Doubled {
+        fn solve(&self, matrix: &[Vec<i32>], m: usize) -> Result<Vec<Vec<i32>>, ErrorCases> {
+            Ok(GaussJordan
+                .solve(matrix, m)?
+                .into_iter()
+                .map(|v| v.into_iter().map(|x| x * 2).collect::<Vec<_>>())
+                .collect::<Vec<_>>())
+        }
+    }
+    assert_eq!(
+        Handler::<i32>::new("H2O=H2+O2")
+            .solver(Doubled)
+            .handle()
+            .unwrap()
+            .coefficients(),
+        vec![vec![4, 4, 2]]
+    );
+    assert_eq!(
+        Handler::<i32>::new("H2O=H2+O2")
+            .solver(Doubled)
+            .minimal_positive()
+            .unwrap()
+            .into_iter()
+            .map(|(_, c)| c)
+            .collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+}
+
+#[test]
+fn unreduced_solver() {
+    // Mixes the Basic Solutions of the default solver, so that no column is touched by only one of them
+    struct Mixed;
+    impl NullSpaceSolver<i32> for Mixed {
+        fn solve(&self, matrix: &[Vec<i32>], m: usize) -> Result<Vec<Vec<i32>>, ErrorCases> {
+            let basis = GaussJordan.solve(matrix, m)?;
+            Ok(vec![
+                basis[0].iter().zip(&basis[1]).map(|(a, b)| a + b).collect(),
+                basis[0].iter().zip(&basis[1]).map(|(a, b)| a - b).collect(),
+            ])
+        }
+    }
+    let equ = "KClO3+HCl=KCl+ClO2+Cl2+H2O";
+    let r = Handler::<i32>::new(equ).solver(Mixed).handle().unwrap();
+    assert_eq!(
+        r.coefficients(),
+        vec![vec![1, 6, 1, 0, 3, 3], vec![-9, -6, -9, -12, 3, -3]]
+    );
+    assert!(r.warnings.is_empty());
+    assert_eq!(
+        Handler::<i32>::new(equ).solver(Mixed).minimal_positive(),
+        Handler::<i32>::new(equ).minimal_positive()
+    );
+    assert_eq!(
+        Handler::<i32>::new(equ)
+            .solver(Mixed)
+            .elementary_reactions(),
+        Handler::<i32>::new(equ).elementary_reactions()
+    );
+}
+
+#[test]
+fn modular() {
+    // The matrix fits in `i32`, but the Basic Solution doesn't.
+    let equ = "A46341B=A+B46341";
+    assert_eq!(
+        Handler::<i32>::new(equ).solver(Modular).handle(),
+        Err(Overflow)
+    );
+    assert_eq!(
+        Handler::<i64>::new(equ)
+            .solver(Modular)
+            .handle()
+            .unwrap()
+            .coefficients(),
+        vec![vec![46341, 46341 * 46341, 1]]
+    );
+}
+
+#[test]
+fn auto_width() {
+    assert_eq!(handle_auto("H2O=H2+O2").unwrap().width(), Width::I32);
+    assert_eq!(
+        handle_auto("(A2147483647)2+A=A").unwrap().width(),
+        Width::I64
+    );
+    assert_eq!(
+        handle_auto("((A2147483647)2147483647)4+A=A")
+            .unwrap()
+            .width(),
+        Width::I128
+    );
+    assert_eq!(handle_auto("A=B"), Err(ZeroSolution));
+    let equ = "(((((((((A32767)32767)32767)32767)32767)32767)32767)32767)32767)=A";
+    #[cfg(not(feature = "bigint"))]
+    assert_eq!(handle_auto(equ), Err(Overflow));
+    #[cfg(feature = "bigint")]
+    assert_eq!(handle_auto(equ).unwrap().width(), Width::BigInt);
+    // The options are kept for every width.
+    let auto = |equ: &str, nuclear: bool| {
+        AutoHandler::new(equ)
+            .nuclear(nuclear)
+            .validate(true)
+            .handle()
+    };
+    assert_eq!(
+        auto("(H2147483647)2+H=H", false).unwrap().width(),
+        Width::I64
+    );
+    assert!(auto("(Xx2147483647)2+Xx=Xx", false).is_err());
+    match auto("U235+n=Ba141+Kr92+3n", true).unwrap() {
+        AutoBalanceResult::I32(r) => assert_eq!(r.elements, ["A", "Z"]),
+        _ => panic!("Not balanced with i32"),
+    }
+}
+
+#[cfg(feature = "bigint")]
+#[test]
+fn bigint() {
+    use num::BigInt;
+    let equ = "(((((((((A32767)32767)32767)32767)32767)32767)32767)32767)32767)=A";
+    tester_error::<i128>(equ, &Overflow);
+    let r = Handler::<BigInt>::new(equ).handle().unwrap();
+    assert_eq!(
+        r.coefficients(),
+        vec![vec![BigInt::from(1), BigInt::from(32767).pow(9)]]
+    );
+    assert_eq!(
+        Handler::<BigInt>::new("KClO3+HCl=KCl+ClO2+Cl2+H2O")
+            .minimal_positive()
+            .unwrap()
+            .into_iter()
+            .map(|(_, c)| c)
+            .collect::<Vec<_>>(),
+        [2, 4, 2, 2, 1, 2]
+            .iter()
+            .map(|c| BigInt::from(*c))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn mass() {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-3;
+    assert!(close(molar_mass("H2O").unwrap(), 18.015));
+    assert!(close(molar_mass("CuSO4.5H2O").unwrap(), 249.677));
+    assert!(close(molar_mass("[13C]O2").unwrap(), 45.001));
+    assert!(close(molar_mass("Fe0.95O").unwrap(), 69.052));
+    assert!(close(
+        molar_mass("SO4^2-").unwrap(),
+        molar_mass("SO4").unwrap()
+    ));
+    let c = composition("NaCl").unwrap();
+    assert_eq!(
+        c.elements
+            .iter()
+            .map(|e| e.element.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Na", "Cl"]
+    );
+    assert!(close(c.elements[0].percent, 39.339));
+    assert!(close(
+        c.elements.iter().map(|e| e.percent).sum::<f64>(),
+        100.0
+    ));
+    assert_eq!(
+        molar_mass("CL2"),
+        Err(parse_error(
+            0..2,
+            &["an element symbol"],
+            Some("CL"),
+            Some("Unknown element 'CL' — did you mean 'Cl'?")
+        ))
+    );
+    assert!(molar_mass("H2O=H2").is_err());
+}
+
+#[test]
+fn verification() {
+    let v = verify::<i32>("CH4+O2=CO2+H2O", &[1, 2, 1, 2]).unwrap();
+    assert!(v.is_balanced());
+    let (left, right) = v.mass.unwrap();
+    assert!((left - 80.039).abs() < 1e-3 && (left - right).abs() < 1e-9);
+    let v = verify::<i32>("CH4+O2=CO2+H2O", &[1, 1, 1, 2]).unwrap();
+    assert!(!v.is_balanced());
+    assert_eq!(
+        v.rows
+            .iter()
+            .map(|r| (r.element.as_str(), r.left, r.right, r.residual))
+            .collect::<Vec<_>>(),
+        vec![
+            ("C", 1, 1, 0),
+            ("H", 4, 4, 0),
+            ("e", 0, 0, 0),
+            ("O", 2, 4, -2)
+        ]
+    );
+    // The charge is a row, and the electrons are massless.
+    let v = verify::<i32>("Fe^3++Cu=Fe^2++Cu^2+", &[2, 1, 2, 1]).unwrap();
+    assert!(v.is_balanced());
+    assert!(v.rows.iter().any(|r| r.element == "e"));
+    assert!(!verify::<i32>("Fe^3++Cu=Fe^2++Cu^2+", &[1, 1, 1, 1])
+        .unwrap()
+        .is_balanced());
+    // There is no molar mass without the periodic table.
+    let v = verify::<i32>("A2=A", &[1, 2]).unwrap();
+    assert!(v.is_balanced() && v.mass.is_none());
+    let v = Handler::<i32>::new("U235+n=Ba141+Kr92+3n")
+        .nuclear(true)
+        .verify(&[1, 2, 1, 1, 1])
+        .unwrap();
+    assert!(!v.is_balanced() && v.mass.is_none());
+    assert_eq!(
+        verify::<i32>("H2+O2=H2O", &[2, 1]),
+        Err(ErrorCases::CoefficientCount(3, 2))
+    );
+    assert_eq!(verify::<i32>("A2147483647=A", &[2, 1]), Err(Overflow));
+    // The trivial solution isn't balanced, and nor is any other zero coefficient.
+    let v = verify::<i32>("H2+O2=H2O", &[0, 0, 0]).unwrap();
+    assert!(v.rows.iter().all(|r| r.residual == 0) && !v.is_balanced());
+    assert!(!verify::<i32>("H2+O2+N2=H2O", &[2, 1, 0, 2])
+        .unwrap()
+        .is_balanced());
+}
+
+#[test]
+fn coefficients() {
+    let v = check::<i32>("2H2+O2=2H2O").unwrap();
+    assert!(v.is_balanced());
+    assert_eq!(
+        v.coefficients,
+        vec![
+            ("H2".to_string(), 2),
+            ("O2".to_string(), 1),
+            ("H2O".to_string(), 2)
+        ]
+    );
+    let v = check::<i32>("2H2 + O2 = H2O").unwrap();
+    assert!(!v.is_balanced());
+    assert_eq!(
+        v.rows
+            .iter()
+            .filter(|r| r.residual != 0)
+            .map(|r| (r.element.as_str(), r.residual))
+            .collect::<Vec<_>>(),
+        vec![("H", 2), ("O", 1)]
+    );
+    // Only the number before the whole chemical formula is a coefficient.
+    let v = check::<i32>("2CuSO4.5H2O=2CuSO4+10H2O").unwrap();
+    assert!(v.is_balanced());
+    assert_eq!(v.coefficients[0], ("CuSO4.5H2O".to_string(), 2));
+    assert!(check::<i32>("2Fe^3+(aq)+Cu(s)=2Fe^2+(aq)+Cu^2+(aq)")
+        .unwrap()
+        .is_balanced());
+    assert!(Handler::<i32>::new("U235+n=Ba141+Kr92+3n")
+        .nuclear(true)
+        .check()
+        .unwrap()
+        .is_balanced());
+    assert_eq!(
+        check::<i32>("1/2O2+H2=H2O"),
+        Err(parse_error(
+            0..3,
+            &[],
+            Some("1/2"),
+            Some("The coefficients must be integers")
+        ))
+    );
+    assert_eq!(check::<i32>("99999999999H2=H2"), Err(Overflow));
+    assert!(!check::<i32>("0H2+0O2=0H2O").unwrap().is_balanced());
+    // The leading numbers are still a part of the chemical formulas while balancing.
+    assert_eq!(
+        Handler::<i32>::new("2H2+O2=2H2O")
+            .handle()
+            .unwrap()
+            .equation
+            .species[0],
+        "2H2"
+    );
+}
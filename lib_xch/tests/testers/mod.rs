@@ -14,36 +14,52 @@
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
 use lib_xch::public::{
-    failures::ErrorCases,
+    failures::{ErrorCases, ParseError},
     handler::Handler,
+    solvers::{FractionFree, GaussJordan, Modular, NullSpaceSolver},
     traits::{CheckedCalc, CheckedType},
 };
+use std::ops::Range;
 
-pub fn tester<T: CheckedType + CheckedCalc>(equ: &str, v: &[&[T]])
-where
-    std::num::ParseIntError: std::convert::From<<T as num::Num>::FromStrRadixErr>
-        + std::convert::From<<T as std::str::FromStr>::Err>,
-{
-    assert_eq!(
-        match Handler::<T>::new(equ).handle() {
-            Ok((_, v)) => v
-                .iter()
-                .map(|x| x.iter().map(|x| **x).collect::<Vec<_>>())
-                .collect::<Vec<_>>(),
-            Err(e) => panic!(e),
-        },
-        v
-    );
+// All the built-in solvers should give the same answer.
+fn solvers<T: CheckedType + CheckedCalc>() -> Vec<Box<dyn NullSpaceSolver<T>>> {
+    vec![
+        Box::new(GaussJordan),
+        Box::new(FractionFree),
+        Box::new(Modular),
+    ]
 }
 
-pub fn tester_error<T: CheckedType + CheckedCalc>(payload: &str, err: &ErrorCases)
-where
-    std::num::ParseIntError: std::convert::From<<T as num::Num>::FromStrRadixErr>
-        + std::convert::From<<T as std::str::FromStr>::Err>,
-{
+pub fn tester<T: CheckedType + CheckedCalc>(equ: &str, v: &[&[T]]) {
+    for solver in solvers::<T>() {
+        assert_eq!(
+            match Handler::<T>::new(equ).solver(solver).handle() {
+                Ok(r) => r.coefficients(),
+                Err(e) => panic!("{}", e),
+            },
+            v
+        );
+    }
+}
+
+pub fn tester_error<T: CheckedType + CheckedCalc>(payload: &str, err: &ErrorCases) {
     if let Err(e) = Handler::<T>::new(payload).handle() {
         assert_eq!(&e, err);
     } else {
         panic!("Failed!"); // `handler_api::<T>` returned `Ok(_)`
     }
 }
+
+pub fn parse_error(
+    span: Range<usize>,
+    expected: &[&str],
+    found: Option<&str>,
+    hint: Option<&str>,
+) -> ErrorCases {
+    ErrorCases::ParserError(ParseError {
+        span,
+        expected: expected.iter().map(|s| s.to_string()).collect(),
+        found: found.map(|s| s.to_string()),
+        hint: hint.map(|s| s.to_string()),
+    })
+}
@@ -20,7 +20,7 @@ fn main() -> Result<(), String> {
     assert_eq!(*a.ok_or("overflow")?.get_data(), 36);
 
     // Addition will result in `None` in the presence of overflow behavior(s)
-    let a = SCell::<i32>::new(std::i32::MAX) + SCell::<i32>::new(1);
+    let a = SCell::<i32>::new(i32::MAX) + SCell::<i32>::new(1);
     assert!(a.is_none());
     Ok(())
 }
@@ -24,7 +24,7 @@
 //!    assert_eq!(*a.ok_or("overflow")?.get_data(), 36);
 //!
 //!    // Addition will result in `None` in the presence of overflow behavior(s)
-//!    let a = SCell::<i32>::new(std::i32::MAX) + SCell::<i32>::new(1);
+//!    let a = SCell::<i32>::new(i32::MAX) + SCell::<i32>::new(1);
 //!    assert!(a.is_none());
 //!    Ok(())
 //! }
@@ -50,31 +50,31 @@ mod tests {
 
     #[test]
     fn overflow_add() {
-        let a = SCell::<i32>::new(std::i32::MAX) + SCell::<i32>::new(1);
+        let a = SCell::<i32>::new(i32::MAX) + SCell::<i32>::new(1);
         assert!(a.is_none());
     }
 
     #[test]
     fn overflow_sub() {
-        let a = SCell::<i32>::new(std::i32::MIN) - SCell::<i32>::new(1);
+        let a = SCell::<i32>::new(i32::MIN) - SCell::<i32>::new(1);
         assert!(a.is_none());
     }
 
     #[test]
     fn overflow_mul() {
-        let a = SCell::<i32>::new(std::i32::MAX) * SCell::<i32>::new(2);
+        let a = SCell::<i32>::new(i32::MAX) * SCell::<i32>::new(2);
         assert!(a.is_none());
     }
 
     #[test]
     fn overflow_div() {
-        let a = SCell::<i32>::new(std::i32::MIN) / SCell::<i32>::new(-1);
+        let a = SCell::<i32>::new(i32::MIN) / SCell::<i32>::new(-1);
         assert!(a.is_none());
     }
 
     #[test]
     fn overflow_abs() {
-        let a = SCell::<i32>::new(std::i32::MIN).abs();
+        let a = SCell::<i32>::new(i32::MIN).abs();
         assert!(a.is_none());
     }
 
@@ -13,14 +13,25 @@
 // You should have received a copy of the GNU General Public License
 // along with this program.  If not, see <http://www.gnu.org/licenses/>.
 
-use lib_xch::public::{handler::Handler, structs::ChemicalEquation};
+use lib_xch::public::{
+    failures::ErrorCases,
+    handler::Handler,
+    structs::{BalanceResult, Diagnosis, Warning},
+};
 use std::io;
 
 fn main() {
     print_about_info();
     let equ = input();
-    match Handler::<i32>::new(&equ).handle() {
-        Ok((c, v)) => print_ans(&c, &v),
+    let mut handler = Handler::<i32>::new(&equ);
+    match handler.handle() {
+        Ok(r) => print_ans(&r),
+        Err(ErrorCases::ZeroSolution) => {
+            println!("{}", ErrorCases::ZeroSolution);
+            if let Ok(d) = handler.diagnose() {
+                print_diagnosis(&d);
+            }
+        }
         Err(e) => println!("{}", e),
     };
 }
@@ -45,9 +56,10 @@ fn input() -> String {
     equ
 }
 
-fn print_ans(c: &ChemicalEquation, vecs: &[Vec<&i32>]) {
+fn print_ans(r: &BalanceResult<i32>) {
+    let vecs = r.coefficients();
     println!("[OUTPUT]:");
-    for i in 0..c.sum {
+    for i in 0..r.equation.sum {
         let mut flag = false;
         if vecs.len() == 1 {
             print!("{}", vecs[0][i])
@@ -76,4 +88,30 @@ fn print_ans(c: &ChemicalEquation, vecs: &[Vec<&i32>]) {
         print!("   ");
     }
     println!();
+    for w in r.warnings.iter() {
+        match w {
+            Warning::AlwaysZero(j, s) => println!("[WARNING] {} (#{}) is always zero", s, j + 1),
+            Warning::WrongSide(j, s) => {
+                println!("[WARNING] {} (#{}) is on the wrong side", s, j + 1)
+            }
+        }
+    }
+}
+
+fn print_diagnosis(d: &Diagnosis) {
+    if !d.one_sided_elements.is_empty() {
+        println!(
+            "[HINT] Only on one side: {}",
+            d.one_sided_elements.join(", ")
+        );
+    }
+    if !d.zero_species.is_empty() {
+        println!("[HINT] Always zero: {}", d.zero_species.join(", "));
+    }
+    if !d.inconsistent_elements.is_empty() {
+        println!(
+            "[HINT] Conflicting elements: {}",
+            d.inconsistent_elements.join(", ")
+        );
+    }
 }